@@ -0,0 +1,259 @@
+use crate::solver::find_formula_statistics;
+use crate::types::formula::{Formula, Node, Operator, find_free_variables};
+use crate::types::transition_system::TransitionSystem;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+
+// A subformula with its variables replaced by (the index of) their binder, as in the local solver
+enum Equation {
+    True,
+    False,
+    And(usize, usize),
+    Or(usize, usize),
+    Diamond(String, usize),
+    Box(String, usize),
+    Fixpoint(usize),
+}
+
+// Specify custom type `GlobalSolver`
+// the equations of a formula without alternation, in blocks of fixed points of the same type. A closed fixed point starts a new
+// block, which only the blocks before it use, so the blocks are solved from the last one to the first one. Each block is solved
+// for all states at once with a worklist and a counter per (equation, state) pair, as by Cleaveland and Steffen.
+
+struct GlobalSolver<'a, T: TransitionSystem + ?Sized> {
+    instance: &'a T,
+    equations: Vec<Equation>,
+    block_of: Vec<usize>,
+    least: Vec<bool>,
+    states: Vec<i64>,
+    positions: HashMap<i64, usize>,
+    successors: HashMap<String, Vec<Vec<usize>>>,
+    predecessors: HashMap<String, Vec<Vec<usize>>>,
+    values: Vec<Vec<bool>>,
+    updates: i64,
+}
+
+/**
+ * Given a Formula f and an LTL instance, calculate the set of states that satisfy f in time linear in the size of f times the
+ * size of the instance, if f has no alternating fixed points (its dependent alternation depth is at most 1). Otherwise returns None.
+ * Also returns the number of (state, subformula) pairs whose value was changed.
+ */
+pub fn execute_alternation_free<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T) -> Option<(HashSet<i64>, i64)> {
+    let (_, _, dependent_alteration_depth) = find_formula_statistics(&f.root_node);
+    if dependent_alteration_depth > 1 {
+        return None;
+    }
+    let states = instance.states();
+    let positions: HashMap<i64, usize> = states.iter().enumerate().map(|(position, state)| (*state, position)).collect();
+    let mut solver = GlobalSolver {
+        instance,
+        equations: Vec::new(),
+        block_of: Vec::new(),
+        least: vec![true],
+        states,
+        positions,
+        successors: HashMap::new(),
+        predecessors: HashMap::new(),
+        values: Vec::new(),
+        updates: 0,
+    };
+    let root = solver.convert(&f.root_node, &HashMap::new(), 0);
+    solver.values = vec![Vec::new(); solver.equations.len()];
+    for block in (0..solver.least.len()).rev() {
+        solver.solve(block);
+    }
+    let result = solver.states.iter().zip(solver.values[root].iter())
+        .filter(|(_, value)| **value)
+        .map(|(state, _)| *state)
+        .collect();
+    Some((result, solver.updates))
+}
+
+impl<'a, T: TransitionSystem + ?Sized> GlobalSolver<'a, T> {
+    fn add_equation(&mut self, equation: Equation, block: usize) -> usize {
+        self.equations.push(equation);
+        self.block_of.push(block);
+        self.equations.len() - 1
+    }
+
+    /**
+     * Turn node into equations in block, where scope maps the variables in scope to the equation of their binder.
+     *   Returns the equation of node.
+     */
+    fn convert(&mut self, node: &Node, scope: &HashMap<String, usize>, block: usize) -> usize {
+        match node {
+            // Like the other solvers, a variable that is not bound is the empty set
+            Node::Variable(variable) => match scope.get(variable) {
+                Some(binder) => *binder,
+                None => self.add_equation(Equation::False, block),
+            },
+            Node::UnaryExpr { op } => {
+                let equation = if *op == Operator::SimpleTrue { Equation::True } else { Equation::False };
+                self.add_equation(equation, block)
+            }
+            Node::BinaryExpr { op, lhs, rhs } => {
+                let rhs_equation = self.convert(rhs, scope, block);
+                let equation = match (op, &**lhs) {
+                    (Operator::DiamondModality, Node::Action(label)) => {
+                        self.index_label(label);
+                        Equation::Diamond(label.clone(), rhs_equation)
+                    }
+                    (Operator::BoxModality, Node::Action(label)) => {
+                        self.index_label(label);
+                        Equation::Box(label.clone(), rhs_equation)
+                    }
+                    (Operator::Conjunction, _) => Equation::And(self.convert(lhs, scope, block), rhs_equation),
+                    (Operator::Disjunction, _) => Equation::Or(self.convert(lhs, scope, block), rhs_equation),
+                    _ => unreachable!("A modality should have an action on its left hand side"),
+                };
+                self.add_equation(equation, block)
+            }
+            Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } => {
+                // A closed fixed point starts a new block, otherwise it has the type of the block of the variables it uses
+                let block = if find_free_variables(node).iter().any(|free| scope.contains_key(free)) {
+                    block
+                } else {
+                    self.least.push(*op == Operator::LeastFixpoint);
+                    self.least.len() - 1
+                };
+                let binder = self.add_equation(Equation::Fixpoint(0), block);
+                let mut scope = scope.clone();
+                scope.insert(variable.clone(), binder);
+                let body = self.convert(rhs, &scope, block);
+                self.equations[binder] = Equation::Fixpoint(body);
+                binder
+            }
+            Node::Action(_) => unreachable!("An action can only occur in a modality"),
+        }
+    }
+
+    /**
+     * Collect the successors and predecessors of every state for label, as positions in `states`
+     */
+    fn index_label(&mut self, label: &str) {
+        if self.successors.contains_key(label) {
+            return;
+        }
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); self.states.len()];
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); self.states.len()];
+        for (position, state) in self.states.iter().enumerate() {
+            for target in self.instance.successors(*state, label) {
+                successors[position].push(self.positions[&target]);
+                predecessors[self.positions[&target]].push(position);
+            }
+        }
+        self.successors.insert(label.to_string(), successors);
+        self.predecessors.insert(label.to_string(), predecessors);
+    }
+
+    /**
+     * Get the (equation, state) pairs the value of equation in the state at position depends on
+     */
+    fn inputs(&self, equation: usize, position: usize) -> Vec<(usize, usize)> {
+        match &self.equations[equation] {
+            Equation::True | Equation::False => Vec::new(),
+            Equation::And(lhs, rhs) | Equation::Or(lhs, rhs) => vec![(*lhs, position), (*rhs, position)],
+            Equation::Fixpoint(body) => vec![(*body, position)],
+            Equation::Diamond(label, body) | Equation::Box(label, body) => {
+                self.successors[label][position].iter().map(|target| (*body, *target)).collect()
+            }
+        }
+    }
+
+    /**
+     * Solve the equations of block, which only use equations of blocks that are solved already.
+     *   Every value starts at false in a least and at true in a greatest fixed point block, and changes at most once. A pair
+     *   changes if one of its inputs changed (any) or if all of them did (all), e.g. a disjunction or a conjunction in a least
+     *   block. The counter of a pair is the number of inputs that still have to change.
+     */
+    fn solve(&mut self, block: usize) {
+        let least = self.least[block];
+        let equations: Vec<usize> = (0..self.equations.len()).filter(|equation| self.block_of[*equation] == block).collect();
+        // The pairs of the block, whose value is `changed == least`, and the pairs of the block that depend on them
+        let mut changed: HashMap<usize, Vec<bool>> = HashMap::new();
+        let mut counters: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut dependents: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut worklist: Vec<(usize, usize)> = Vec::new();
+        for equation in equations.iter() {
+            let any = match self.equations[*equation] {
+                Equation::Or(_, _) | Equation::Diamond(_, _) => least,
+                Equation::And(_, _) | Equation::Box(_, _) => !least,
+                _ => true,
+            };
+            let constant = match self.equations[*equation] {
+                Equation::True => Some(least),
+                Equation::False => Some(!least),
+                _ => None,
+            };
+            if let Equation::And(lhs, rhs) | Equation::Or(lhs, rhs) = self.equations[*equation] {
+                dependents.entry(lhs).or_default().push(*equation);
+                dependents.entry(rhs).or_default().push(*equation);
+            }
+            if let Equation::Fixpoint(body) | Equation::Diamond(_, body) | Equation::Box(_, body) = self.equations[*equation] {
+                dependents.entry(body).or_default().push(*equation);
+            }
+            let mut equation_changed = vec![false; self.states.len()];
+            let mut equation_counters = vec![0; self.states.len()];
+            for position in 0..self.states.len() {
+                // Inputs of other blocks have their final value
+                let mut counter = 0;
+                let mut changes = constant.unwrap_or(false);
+                for (input, target) in self.inputs(*equation, position) {
+                    if self.block_of[input] != block {
+                        if self.values[input][target] == least {
+                            changes |= any;
+                        } else if !any {
+                            counter = usize::MAX;
+                        }
+                    } else if counter != usize::MAX {
+                        counter += 1;
+                    }
+                }
+                if constant.is_none() && !any && counter == 0 {
+                    changes = true;
+                }
+                if changes {
+                    equation_changed[position] = true;
+                    worklist.push((*equation, position));
+                }
+                equation_counters[position] = counter;
+            }
+            changed.insert(*equation, equation_changed);
+            counters.insert(*equation, equation_counters);
+        }
+
+        while let Some((input, target)) = worklist.pop() {
+            self.updates += 1;
+            for dependent in dependents.get(&input).cloned().unwrap_or_default() {
+                let positions: Vec<usize> = match &self.equations[dependent] {
+                    Equation::Diamond(label, _) | Equation::Box(label, _) => self.predecessors[label][target].clone(),
+                    _ => vec![target],
+                };
+                let any = match self.equations[dependent] {
+                    Equation::Or(_, _) | Equation::Diamond(_, _) => least,
+                    Equation::And(_, _) | Equation::Box(_, _) => !least,
+                    _ => true,
+                };
+                for position in positions {
+                    if changed[&dependent][position] {
+                        continue;
+                    }
+                    let counter = &mut counters.get_mut(&dependent).unwrap()[position];
+                    if !any && *counter != usize::MAX {
+                        *counter -= 1;
+                    }
+                    if any || *counter == 0 {
+                        changed.get_mut(&dependent).unwrap()[position] = true;
+                        worklist.push((dependent, position));
+                    }
+                }
+            }
+        }
+
+        for equation in equations {
+            self.values[equation] = changed[&equation].iter().map(|changed| *changed == least).collect();
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::compression::{open_file, strip_compression_extension};
+use crate::types::ltl::Ltl;
+
+use std::io::BufRead;
+
+
+// The header `des (first_state, nr_of_transitions, nr_of_states)` and the transitions (start_state, label, end_state) of an .aut file
+pub type AutFile = ((i64, i64, i64), Vec<(i64, String, i64)>);
+
+/**
+ * Read .aut file and return its header (first state, nr of transitions, nr of states) and the transitions in its body
+ */
+pub fn parse_aut_file(file_path: std::path::PathBuf) -> AutFile {
+    if !file_path.exists() {
+        panic!("File {:?} does not exist", file_path);
+    }
+
+    // The file may be compressed, e.g. `dining_2.aut.gz`
+    if Some(std::ffi::OsStr::new("aut")) != strip_compression_extension(&file_path).extension() {
+        panic!("File {:?} should have been of type .aut", file_path);
+    }
+
+    // Read the (possibly compressed) file line by line, so we never hold the whole file in memory
+    let mut lines = open_file(&file_path).lines()
+        .map(|line| line.expect("Should have been able to read the file"));
+
+    // Initialize header, e.g. "des (123,456,789)     "
+    let first_line = lines.next()
+        .expect("File cannot be empty");
+
+    let (des, last) = first_line.split_at(5);
+    if "des (" != des {
+        panic!(".aut file should contain `des (...)` as first line, file started with {}", des);
+    }
+
+    let seconds: Vec<&str> = last.split(")").collect();
+    let nums: Vec<&str> = seconds[0].split(",").collect();
+    let header = (to_int64(nums[0].trim()), to_int64(nums[1].trim()), to_int64(nums[2].trim()));
+
+    // read transitions, every line after the header is one transition
+    let mut transitions: Vec<(i64, String, i64)> = Vec::new();
+    for part in lines.filter(|line| !line.trim().is_empty()) {
+        let part = part.as_str();
+        let (start, last) = part.split_at(1);
+        if "(" != start {
+            panic!("Line '{}' did not start with '('", part)
+        }
+        // The label is quoted and may itself contain commas and parentheses, e.g. (0,"lock(p1, f1)",2)
+        let first_quote = last.find('"')
+            .unwrap_or_else(|| panic!("No label found in {}", part));
+        let last_quote = last.rfind('"')
+            .unwrap_or_else(|| panic!("No label found in {}", part));
+        let label: &str = &last[first_quote+1..last_quote];
+        let start_state = last[..first_quote].trim().trim_end_matches(',');
+        let end_state = last[last_quote+1..].trim().trim_start_matches(',').trim_end().trim_end_matches(')');
+
+        transitions.push((to_int64(start_state.trim()), label.to_string(), to_int64(end_state.trim())));
+    }
+
+    (header, transitions)
+}
+
+fn to_int64(f: &str) -> i64 {
+    f.parse::<i64>().unwrap()
+}
+
+/**
+ * Read .aut file and convert to DataType
+ */
+pub fn read_aut_file(file_path: std::path::PathBuf, debug: bool) -> Ltl {
+    let ((first_state, nr_of_transitions, nr_of_states), transitions) = parse_aut_file(file_path);
+
+    let mut ltl: Ltl = Ltl::new(
+        first_state,
+        nr_of_transitions, 
+        nr_of_states
+    );
+
+    // initialize transitions
+    for (start_state, label, end_state) in transitions {
+        ltl.add_transition(
+            start_state, 
+            &label, 
+            end_state,
+            debug
+        );
+    }
+
+    ltl
+}
@@ -0,0 +1,23 @@
+use crate::solver::{execute_from, Options, Statistics};
+use crate::types::formula::Formula;
+use crate::types::transition_system::TransitionSystem;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+
+/**
+ * Given formulas and a transition system instance, evaluate every formula on the instance as execute_with_options does.
+ *   Identical closed subformulas, e.g. `<plato>true` or `mu Y. (<i>Y || <plato>true)`, are evaluated once for all formulas,
+ *   a later occurrence takes its result set from the first one and counts as a cache hit.
+ *   Returns the result set and the statistics of every formula, in the same order.
+ */
+pub fn execute_batch<T: TransitionSystem + ?Sized>(formulas: Vec<Formula>, instance: &T, options: &Options) -> Vec<(HashSet<i64>, Statistics)> {
+    let mut shared: HashMap<String, HashSet<i64>> = HashMap::new();
+    formulas.iter()
+        .map(|f| {
+            let (result, statistics, _) = execute_from(f, instance, options, &HashMap::new(), &mut shared);
+            (result, statistics)
+        })
+        .collect()
+}
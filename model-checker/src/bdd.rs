@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+
+// Specify custom type `Bdd`
+// a reduced ordered binary decision diagram, as the index of its root in the node table of a `BddManager`. The nodes are
+// shared and reduced, so two Bdds of the same manager represent the same boolean function if and only if they are equal.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bdd(usize);
+
+impl Bdd {
+    pub const FALSE: Bdd = Bdd(0);
+    pub const TRUE: Bdd = Bdd(1);
+}
+
+// A node tests `variable`, and continues with `low` if it is false and with `high` if it is true.
+// The terminals have variable usize::MAX, so they come after every variable in the order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct BddNode {
+    variable: usize,
+    low: Bdd,
+    high: Bdd,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Operation {
+    Not,
+    And,
+    Or,
+    AndExists,
+}
+
+// Specify custom type `BddManager`
+// the node table of a set of Bdds over the variables 0, 1, 2, ..., ordered by their number, with the unique table that keeps
+// the nodes shared and a cache of the results of the operations on them.
+
+pub struct BddManager {
+    nodes: Vec<BddNode>,
+    unique: HashMap<BddNode, Bdd>,
+    cache: HashMap<(Operation, Bdd, Bdd, Bdd), Bdd>,
+}
+
+// The cache is cleared when it grows beyond this many results, the nodes are kept
+const CACHE_LIMIT: usize = 1 << 22;
+
+impl Default for BddManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BddManager {
+    pub fn new() -> Self {
+        let terminal = |value: Bdd| BddNode { variable: usize::MAX, low: value, high: value };
+        BddManager {
+            nodes: vec![terminal(Bdd::FALSE), terminal(Bdd::TRUE)],
+            unique: HashMap::new(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /**
+     * Get the number of nodes in the table, including the terminals
+     */
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /**
+     * Get the node `if variable then high else low`, where low and high do not test variables up to `variable`
+     */
+    fn make(&mut self, variable: usize, low: Bdd, high: Bdd) -> Bdd {
+        if low == high {
+            return low;
+        }
+        let node = BddNode { variable, low, high };
+        if let Some(bdd) = self.unique.get(&node) {
+            return *bdd;
+        }
+        let bdd = Bdd(self.nodes.len());
+        self.nodes.push(node);
+        self.unique.insert(node, bdd);
+        bdd
+    }
+
+    fn top(&self, f: Bdd) -> usize {
+        self.nodes[f.0].variable
+    }
+
+    /**
+     * Get the cofactors of f for `variable` = false and `variable` = true, where variable is at most the top variable of f
+     */
+    fn cofactors(&self, f: Bdd, variable: usize) -> (Bdd, Bdd) {
+        let node = self.nodes[f.0];
+        if node.variable == variable { (node.low, node.high) } else { (f, f) }
+    }
+
+    fn cached(&mut self, key: (Operation, Bdd, Bdd, Bdd), result: Bdd) -> Bdd {
+        if self.cache.len() >= CACHE_LIMIT {
+            self.cache.clear();
+        }
+        self.cache.insert(key, result);
+        result
+    }
+
+    /**
+     * Get the conjunction of the literals in assignment, where (v, true) is v and (v, false) is not v
+     */
+    pub fn cube(&mut self, assignment: &[(usize, bool)]) -> Bdd {
+        let mut literals = assignment.to_vec();
+        literals.sort();
+        literals.into_iter().rev().fold(Bdd::TRUE, |result, (variable, value)| {
+            if value { self.make(variable, Bdd::FALSE, result) } else { self.make(variable, result, Bdd::FALSE) }
+        })
+    }
+
+    /**
+     * Get the function that is true for exactly the given assignments to `variables`, which are in increasing order.
+     *   Building it at once is much faster than taking the disjunction of a cube per assignment.
+     */
+    pub fn assignments(&mut self, variables: &[usize], mut assignments: Vec<Vec<bool>>) -> Bdd {
+        assignments.sort();
+        assignments.dedup();
+        self.build(variables, &assignments, 0)
+    }
+
+    // The assignments are sorted, so those where variables[level] is false come before those where it is true
+    fn build(&mut self, variables: &[usize], assignments: &[Vec<bool>], level: usize) -> Bdd {
+        if assignments.is_empty() {
+            return Bdd::FALSE;
+        }
+        if level == variables.len() {
+            return Bdd::TRUE;
+        }
+        let split = assignments.partition_point(|assignment| !assignment[level]);
+        let low = self.build(variables, &assignments[..split], level + 1);
+        let high = self.build(variables, &assignments[split..], level + 1);
+        self.make(variables[level], low, high)
+    }
+
+    /**
+     * Evaluate f for the assignment that gives variable v the value `assignment(v)`
+     */
+    pub fn evaluate(&self, f: Bdd, assignment: impl Fn(usize) -> bool) -> bool {
+        let mut current = f;
+        while current != Bdd::FALSE && current != Bdd::TRUE {
+            let node = self.nodes[current.0];
+            current = if assignment(node.variable) { node.high } else { node.low };
+        }
+        current == Bdd::TRUE
+    }
+
+    pub fn not(&mut self, f: Bdd) -> Bdd {
+        if f == Bdd::FALSE {
+            return Bdd::TRUE;
+        }
+        if f == Bdd::TRUE {
+            return Bdd::FALSE;
+        }
+        let key = (Operation::Not, f, f, f);
+        if let Some(result) = self.cache.get(&key) {
+            return *result;
+        }
+        let node = self.nodes[f.0];
+        let low = self.not(node.low);
+        let high = self.not(node.high);
+        let result = self.make(node.variable, low, high);
+        self.cached(key, result)
+    }
+
+    pub fn and(&mut self, f: Bdd, g: Bdd) -> Bdd {
+        if f == Bdd::FALSE || g == Bdd::FALSE {
+            return Bdd::FALSE;
+        }
+        if f == Bdd::TRUE || f == g {
+            return g;
+        }
+        if g == Bdd::TRUE {
+            return f;
+        }
+        self.apply(Operation::And, f.min(g), f.max(g))
+    }
+
+    pub fn or(&mut self, f: Bdd, g: Bdd) -> Bdd {
+        if f == Bdd::TRUE || g == Bdd::TRUE {
+            return Bdd::TRUE;
+        }
+        if f == Bdd::FALSE || f == g {
+            return g;
+        }
+        if g == Bdd::FALSE {
+            return f;
+        }
+        self.apply(Operation::Or, f.min(g), f.max(g))
+    }
+
+    // Apply a commutative operation to two Bdds that are not terminals, recursively on their cofactors
+    fn apply(&mut self, operation: Operation, f: Bdd, g: Bdd) -> Bdd {
+        let key = (operation, f, g, g);
+        if let Some(result) = self.cache.get(&key) {
+            return *result;
+        }
+        let variable = self.top(f).min(self.top(g));
+        let (f_low, f_high) = self.cofactors(f, variable);
+        let (g_low, g_high) = self.cofactors(g, variable);
+        let (low, high) = if operation == Operation::And {
+            (self.and(f_low, g_low), self.and(f_high, g_high))
+        } else {
+            (self.or(f_low, g_low), self.or(f_high, g_high))
+        };
+        let result = self.make(variable, low, high);
+        self.cached(key, result)
+    }
+
+    /**
+     * Get the function `exists variables. f && g`, where variables is a cube of positive literals, without building f && g.
+     *   This is the relational product, e.g. the image of a set of states under a transition relation.
+     */
+    pub fn and_exists(&mut self, f: Bdd, g: Bdd, variables: Bdd) -> Bdd {
+        if f == Bdd::FALSE || g == Bdd::FALSE {
+            return Bdd::FALSE;
+        }
+        if f == Bdd::TRUE && g == Bdd::TRUE {
+            return Bdd::TRUE;
+        }
+        let variable = self.top(f).min(self.top(g));
+        // Quantified variables above the top variable do not occur in f && g
+        let mut variables = variables;
+        while self.top(variables) < variable {
+            variables = self.nodes[variables.0].high;
+        }
+        if variables == Bdd::TRUE {
+            return self.and(f, g);
+        }
+        let (f, g) = (f.min(g), f.max(g));
+        let key = (Operation::AndExists, f, g, variables);
+        if let Some(result) = self.cache.get(&key) {
+            return *result;
+        }
+        let (f_low, f_high) = self.cofactors(f, variable);
+        let (g_low, g_high) = self.cofactors(g, variable);
+        let result = if self.top(variables) == variable {
+            let rest = self.nodes[variables.0].high;
+            let low = self.and_exists(f_low, g_low, rest);
+            if low == Bdd::TRUE {
+                Bdd::TRUE
+            } else {
+                let high = self.and_exists(f_high, g_high, rest);
+                self.or(low, high)
+            }
+        } else {
+            let low = self.and_exists(f_low, g_low, variables);
+            let high = self.and_exists(f_high, g_high, variables);
+            self.make(variable, low, high)
+        };
+        self.cached(key, result)
+    }
+
+    /**
+     * Rename the variables of f with `renaming`, variables that are not in it keep their name.
+     *   The renaming has to keep the order of the variables of f, e.g. x_i to x_i' where x_i' comes right after x_i.
+     */
+    pub fn rename(&mut self, f: Bdd, renaming: &HashMap<usize, usize>) -> Bdd {
+        let mut renamed: HashMap<Bdd, Bdd> = HashMap::new();
+        self.rename_node(f, renaming, &mut renamed)
+    }
+
+    fn rename_node(&mut self, f: Bdd, renaming: &HashMap<usize, usize>, renamed: &mut HashMap<Bdd, Bdd>) -> Bdd {
+        if f == Bdd::FALSE || f == Bdd::TRUE {
+            return f;
+        }
+        if let Some(result) = renamed.get(&f) {
+            return *result;
+        }
+        let node = self.nodes[f.0];
+        let low = self.rename_node(node.low, renaming, renamed);
+        let high = self.rename_node(node.high, renaming, renamed);
+        let result = self.make(*renaming.get(&node.variable).unwrap_or(&node.variable), low, high);
+        renamed.insert(f, result);
+        result
+    }
+}
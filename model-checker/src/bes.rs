@@ -0,0 +1,525 @@
+use crate::types::formula::{Formula, Node, Operator};
+use crate::types::transition_system::TransitionSystem;
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write;
+
+
+// Specify custom type `Bes`
+// a boolean equation system: a sequence of equations `mu X = e` or `nu X = e`, where e is a boolean expression over the
+// variables of the system. Earlier equations take precedence over later ones, like outer fixed points over inner ones.
+
+pub struct Bes {
+    pub equations: Vec<Equation>,
+    pub init: usize,
+}
+
+pub struct Equation {
+    pub fixpoint: Operator,
+    pub variable: String,
+    pub rhs: Expression,
+}
+
+// A boolean expression, where a variable is the index of its equation
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    True,
+    False,
+    Variable(usize),
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
+}
+
+/**
+ * Given a Formula f and an LTL instance, calculate the set of states that satisfy f by solving the boolean equation system
+ * of f and the instance with Gauss elimination. Also returns the number of equations.
+ */
+pub fn execute_gauss<T: TransitionSystem + ?Sized>(f: Formula, instance: &T) -> (HashSet<i64>, i64) {
+    let bes = to_bes(&f, instance);
+    let values = solve_gauss(&bes);
+    let mut states: Vec<i64> = instance.states();
+    states.sort();
+    let result = states.into_iter().enumerate().filter(|(index, _)| values[*index]).map(|(_, state)| state).collect();
+    (result, bes.equations.len() as i64)
+}
+
+/**
+ * Translate a Formula f and an LTL instance into a boolean equation system, with an equation `X_s` for every fixed point
+ * `mu X` or `nu X` in f and every state s, stating that s satisfies the fixed point. The equations of a fixed point are in
+ * the order of the states, and fixed points are ordered from the outside in.
+ *
+ * Equation k is for the k-th state and f itself, if f is not a fixed point it gets an extra greatest fixed point `F`.
+ * The init variable is for the initial state. Variables that are renamed to keep them apart get a `'`, e.g. `X'_0`.
+ */
+pub fn to_bes<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T) -> Bes {
+    let mut states: Vec<i64> = instance.states();
+    states.sort();
+    let positions: HashMap<i64, usize> = states.iter().enumerate().map(|(position, state)| (*state, position)).collect();
+
+    // Find the fixed points of f from the outside in, where a formula that is not a fixed point is wrapped in one
+    let root = Node::FixPointExpr {
+        op: Operator::GreatestFixpoint,
+        variable: String::new(),
+        rhs: Box::new(f.root_node.clone()),
+        surrounding_binder: Operator::None,
+    };
+    let mut binders: Vec<(&Node, String)> = Vec::new();
+    match &f.root_node {
+        Node::FixPointExpr { .. } => find_binders(&f.root_node, &mut binders),
+        _ => find_binders(&root, &mut binders),
+    }
+
+    let translation = Translation { instance, positions: &positions, binders: &binders, nr_of_states: states.len() };
+    let mut equations: Vec<Equation> = Vec::new();
+    for (index, (binder, name)) in binders.iter().enumerate() {
+        let Node::FixPointExpr { op, variable: _, rhs, surrounding_binder: _ } = binder else { unreachable!() };
+        // The binders in scope of the body of this binder, by their variable
+        let scope: HashMap<&str, usize> = binders.iter().enumerate()
+            .filter(|(_, (outer, _))| contains(outer, binder))
+            .map(|(outer_index, (outer, _))| (variable_of(outer), outer_index * states.len()))
+            .collect();
+        for state in states.iter() {
+            equations.push(Equation {
+                fixpoint: op.clone(),
+                variable: format!("{}_{}", name, state),
+                rhs: translation.expression(rhs, *state, &scope, index),
+            });
+        }
+    }
+
+    Bes { equations, init: positions[&instance.initial_state()] }
+}
+
+// What the translation of a body to an expression needs to know about the LTS and the fixed points
+struct Translation<'a, T: TransitionSystem + ?Sized> {
+    instance: &'a T,
+    positions: &'a HashMap<i64, usize>,
+    binders: &'a [(&'a Node, String)],
+    nr_of_states: usize,
+}
+
+impl<'a, T: TransitionSystem + ?Sized> Translation<'a, T> {
+    /**
+     * Translate node in state to an expression, where scope maps the variables in scope to the first equation of their
+     *   binder, and `binder` is the index of the binder whose body contains node.
+     */
+    fn expression(&self, node: &Node, state: i64, scope: &HashMap<&str, usize>, binder: usize) -> Expression {
+        match node {
+            // Like the other solvers, a variable that is not bound is the empty set
+            Node::Variable(variable) => match scope.get(variable.as_str()) {
+                Some(first) => Expression::Variable(first + self.positions[&state]),
+                None => Expression::False,
+            },
+            Node::UnaryExpr { op } => {
+                if *op == Operator::SimpleTrue { Expression::True } else { Expression::False }
+            }
+            Node::BinaryExpr { op, lhs, rhs } => {
+                let operands: Vec<Expression> = match (op, &**lhs) {
+                    (Operator::DiamondModality, Node::Action(label)) | (Operator::BoxModality, Node::Action(label)) => {
+                        let mut targets: Vec<i64> = self.instance.successors(state, label);
+                        targets.sort();
+                        targets.dedup();
+                        targets.into_iter().map(|target| self.expression(rhs, target, scope, binder)).collect()
+                    }
+                    _ => vec![self.expression(lhs, state, scope, binder), self.expression(rhs, state, scope, binder)],
+                };
+                if *op == Operator::Conjunction || *op == Operator::BoxModality {
+                    simplify(Expression::And(operands))
+                } else {
+                    simplify(Expression::Or(operands))
+                }
+            }
+            Node::FixPointExpr { .. } => {
+                // The next binders in preorder are those inside the current binder, find the one that is node
+                let index = (binder..self.binders.len()).find(|index| std::ptr::eq(self.binders[*index].0, node))
+                    .expect("A fixed point should have been found as a binder");
+                Expression::Variable(index * self.nr_of_states + self.positions[&state])
+            }
+            Node::Action(_) => unreachable!("An action can only occur in a modality"),
+        }
+    }
+}
+
+/**
+ * Solve the boolean equation system with Gauss elimination, returning the value of every variable.
+ *
+ * From the last equation to the first, the equation `mu X = e` is solved as X = e[X := false] (for nu, X = e[X := true]),
+ * and this solution is substituted for X in all earlier equations. Afterwards equation i only refers to earlier equations,
+ * so the values follow from the first equation to the last. Expressions may grow large, so this is meant for small systems.
+ */
+pub fn solve_gauss(bes: &Bes) -> Vec<bool> {
+    let mut rhs: Vec<Expression> = bes.equations.iter().map(|equation| equation.rhs.clone()).collect();
+
+    // The equations that refer to each variable, which may still contain equations that no longer do after simplification
+    let mut users: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); rhs.len()];
+    for (user, expression) in rhs.iter().enumerate() {
+        let mut variables: Vec<usize> = Vec::new();
+        find_variables(expression, &mut variables);
+        for variable in variables {
+            users[variable].insert(user);
+        }
+    }
+
+    for variable in (0..rhs.len()).rev() {
+        let own_value = if bes.equations[variable].fixpoint == Operator::LeastFixpoint { Expression::False } else { Expression::True };
+        rhs[variable] = substitute(&rhs[variable], variable, &own_value);
+        let mut variables: Vec<usize> = Vec::new();
+        find_variables(&rhs[variable], &mut variables);
+        let earlier_users: Vec<usize> = users[variable].range(..variable).cloned().collect();
+        for earlier in earlier_users {
+            rhs[earlier] = substitute(&rhs[earlier], variable, &rhs[variable]);
+            for other in variables.iter() {
+                users[*other].insert(earlier);
+            }
+        }
+    }
+
+    let mut values: Vec<bool> = Vec::with_capacity(rhs.len());
+    for expression in rhs.iter() {
+        let value = evaluate(expression, &|variable| values[variable]);
+        values.push(value);
+    }
+    values
+}
+
+/**
+ * Solve the boolean equation system locally, returning the value of `variable`.
+ *
+ * Only the equations that `variable` depends on are solved. Consecutive equations of the same fixed point form a block,
+ * and every block is solved by iterating it, solving all blocks after it from scratch for every approximation.
+ */
+pub fn solve_local(bes: &Bes, variable: usize) -> bool {
+    // Find the equations that variable depends on
+    let mut relevant: Vec<bool> = vec![false; bes.equations.len()];
+    relevant[variable] = true;
+    let mut stack: Vec<usize> = vec![variable];
+    while let Some(next) = stack.pop() {
+        let mut dependencies: Vec<usize> = Vec::new();
+        find_variables(&bes.equations[next].rhs, &mut dependencies);
+        for dependency in dependencies {
+            if !relevant[dependency] {
+                relevant[dependency] = true;
+                stack.push(dependency);
+            }
+        }
+    }
+
+    // Split the relevant equations into blocks of the same fixed point
+    let mut blocks: Vec<Vec<usize>> = Vec::new();
+    for (index, equation) in bes.equations.iter().enumerate().filter(|(index, _)| relevant[*index]) {
+        match blocks.last_mut() {
+            Some(block) if bes.equations[block[0]].fixpoint == equation.fixpoint => block.push(index),
+            _ => blocks.push(vec![index]),
+        }
+    }
+
+    let mut values: Vec<bool> = vec![false; bes.equations.len()];
+    solve_blocks(bes, &blocks, &mut values);
+    values[variable]
+}
+
+/**
+ * Solve the first block in blocks, given the values of the blocks before it, and all blocks after it
+ */
+fn solve_blocks(bes: &Bes, blocks: &[Vec<usize>], values: &mut [bool]) {
+    let Some((block, inner)) = blocks.split_first() else {
+        return;
+    };
+    let initial = bes.equations[block[0]].fixpoint != Operator::LeastFixpoint;
+    for variable in block {
+        values[*variable] = initial;
+    }
+    loop {
+        solve_blocks(bes, inner, values);
+        let approximation: Vec<bool> = block.iter().map(|variable| evaluate(&bes.equations[*variable].rhs, &|v| values[v])).collect();
+        if block.iter().zip(&approximation).all(|(variable, value)| values[*variable] == *value) {
+            return;
+        }
+        for (variable, value) in block.iter().zip(approximation) {
+            values[*variable] = value;
+        }
+    }
+}
+
+impl Bes {
+    /**
+     * Read a boolean equation system in the textual format of `write`, where `%` starts a comment, e.g.
+     *   pbes
+     *     nu X_0 = (X_1 && Y_0);
+     *     mu Y_0 = (X_0 || false);
+     *   init X_0;
+     */
+    pub fn parse(text: &str) -> Self {
+        let tokens: Vec<String> = tokenize(text);
+        let mut position: usize = 0;
+        let mut next = || {
+            let token = tokens.get(position).cloned().unwrap_or_default();
+            position += 1;
+            token
+        };
+
+        let expect = |token: String, expected: &str| {
+            if token != expected {
+                panic!("Expected `{}` in the boolean equation system, found `{}`", expected, token);
+            }
+        };
+        expect(next(), "pbes");
+
+        // Read the equations with their right hand sides as tokens, variables can be used before their equation
+        let mut equations: Vec<(Operator, String, Vec<String>)> = Vec::new();
+        let init: String = loop {
+            let token = next();
+            let fixpoint = match token.as_str() {
+                "mu" => Operator::LeastFixpoint,
+                "nu" => Operator::GreatestFixpoint,
+                "init" => {
+                    let init = next();
+                    expect(next(), ";");
+                    break init;
+                }
+                _ => panic!("Expected `mu`, `nu` or `init` in the boolean equation system, found `{}`", token),
+            };
+            let variable = next();
+            expect(next(), "=");
+            let mut rhs: Vec<String> = Vec::new();
+            loop {
+                match next().as_str() {
+                    ";" => break,
+                    "" => panic!("Expected `;` after the equation of {}", variable),
+                    token => rhs.push(token.to_string()),
+                }
+            }
+            equations.push((fixpoint, variable, rhs));
+        };
+
+        let indices: HashMap<String, usize> = equations.iter().enumerate()
+            .map(|(index, (_, variable, _))| (variable.clone(), index))
+            .collect();
+        let equations: Vec<Equation> = equations.into_iter().map(|(fixpoint, variable, rhs)| {
+            let mut parser = ExpressionParser { tokens: &rhs, position: 0, indices: &indices };
+            let expression = parser.disjunction();
+            if parser.position != rhs.len() {
+                panic!("Unexpected `{}` in the equation of {}", rhs[parser.position], variable);
+            }
+            Equation { fixpoint, variable, rhs: expression }
+        }).collect();
+        let init = *indices.get(&init)
+            .unwrap_or_else(|| panic!("The init variable {} should have an equation", init));
+
+        Bes { equations, init }
+    }
+
+    /**
+     * Write the boolean equation system in the textual format of mCRL2
+     */
+    pub fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "pbes")?;
+        for equation in self.equations.iter() {
+            let binder = if equation.fixpoint == Operator::LeastFixpoint { "mu" } else { "nu" };
+            writeln!(writer, "  {} {} = {};", binder, equation.variable, self.expression_to_string(&equation.rhs))?;
+        }
+        writeln!(writer, "\ninit {};", self.equations[self.init].variable)
+    }
+
+    fn expression_to_string(&self, expression: &Expression) -> String {
+        match expression {
+            Expression::True => String::from("true"),
+            Expression::False => String::from("false"),
+            Expression::Variable(variable) => self.equations[*variable].variable.clone(),
+            Expression::And(operands) | Expression::Or(operands) => {
+                let separator = if matches!(expression, Expression::And(_)) { " && " } else { " || " };
+                let operands: Vec<String> = operands.iter().map(|operand| self.expression_to_string(operand)).collect();
+                format!("({})", operands.join(separator))
+            }
+        }
+    }
+}
+
+// A recursive descent parser of the tokens of an expression, where && binds stronger than ||
+struct ExpressionParser<'a> {
+    tokens: &'a [String],
+    position: usize,
+    indices: &'a HashMap<String, usize>,
+}
+
+impl<'a> ExpressionParser<'a> {
+    fn disjunction(&mut self) -> Expression {
+        let mut operands: Vec<Expression> = vec![self.conjunction()];
+        while self.tokens.get(self.position).is_some_and(|token| token == "||") {
+            self.position += 1;
+            operands.push(self.conjunction());
+        }
+        simplify(Expression::Or(operands))
+    }
+
+    fn conjunction(&mut self) -> Expression {
+        let mut operands: Vec<Expression> = vec![self.atom()];
+        while self.tokens.get(self.position).is_some_and(|token| token == "&&") {
+            self.position += 1;
+            operands.push(self.atom());
+        }
+        simplify(Expression::And(operands))
+    }
+
+    fn atom(&mut self) -> Expression {
+        let token = self.tokens.get(self.position).cloned().unwrap_or_default();
+        self.position += 1;
+        match token.as_str() {
+            "true" => Expression::True,
+            "false" => Expression::False,
+            "(" => {
+                let expression = self.disjunction();
+                if self.tokens.get(self.position).is_none_or(|token| token != ")") {
+                    panic!("Expected `)` in the boolean equation system");
+                }
+                self.position += 1;
+                expression
+            }
+            variable => Expression::Variable(*self.indices.get(variable)
+                .unwrap_or_else(|| panic!("The variable `{}` should have an equation", variable))),
+        }
+    }
+}
+
+/**
+ * Split text into identifiers and the symbols `(`, `)`, `&&`, `||`, `=` and `;`, skipping comments that start with `%`
+ */
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.split('%').next().unwrap_or_default();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                c if c.is_whitespace() => {}
+                '(' | ')' | '=' | ';' => tokens.push(c.to_string()),
+                '&' | '|' => {
+                    if chars.next() != Some(c) {
+                        panic!("Expected `{}{}` in the boolean equation system", c, c);
+                    }
+                    tokens.push(format!("{}{}", c, c));
+                }
+                c if c.is_alphanumeric() || c == '_' || c == '\'' => {
+                    let mut identifier = c.to_string();
+                    while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '\'') {
+                        identifier.push(c);
+                    }
+                    tokens.push(identifier);
+                }
+                _ => panic!("Unexpected character `{}` in the boolean equation system", c),
+            }
+        }
+    }
+    tokens
+}
+
+/**
+ * Find the fixed points in node in preorder, with a name for their variable that no earlier fixed point uses
+ */
+fn find_binders<'a>(node: &'a Node, binders: &mut Vec<(&'a Node, String)>) {
+    match node {
+        Node::BinaryExpr { op: _, lhs, rhs } => {
+            find_binders(lhs, binders);
+            find_binders(rhs, binders);
+        }
+        Node::FixPointExpr { op: _, variable, rhs, surrounding_binder: _ } => {
+            // The fixed point around a formula that is not a fixed point has no variable
+            let mut name = if variable.is_empty() { String::from("F") } else { variable.clone() };
+            while binders.iter().any(|(_, other)| *other == name) {
+                name.push('\'');
+            }
+            binders.push((node, name));
+            find_binders(rhs, binders);
+        }
+        _ => {}
+    }
+}
+
+/**
+ * Check whether the fixed point `inner` is node or occurs in it
+ */
+fn contains(node: &Node, inner: &Node) -> bool {
+    std::ptr::eq(node, inner) || match node {
+        Node::BinaryExpr { op: _, lhs, rhs } => contains(lhs, inner) || contains(rhs, inner),
+        Node::FixPointExpr { op: _, variable: _, rhs, surrounding_binder: _ } => contains(rhs, inner),
+        _ => false,
+    }
+}
+
+fn variable_of(binder: &Node) -> &str {
+    match binder {
+        Node::FixPointExpr { op: _, variable, rhs: _, surrounding_binder: _ } => variable,
+        _ => unreachable!("A binder should be a fixed point"),
+    }
+}
+
+/**
+ * Flatten nested conjunctions and disjunctions, and remove the constants from them where possible
+ */
+fn simplify(expression: Expression) -> Expression {
+    let (operands, is_and) = match expression {
+        Expression::And(operands) => (operands, true),
+        Expression::Or(operands) => (operands, false),
+        _ => return expression,
+    };
+    // true is the unit of a conjunction and false of a disjunction, the other constant is absorbing
+    let (unit, absorbing) = if is_and { (Expression::True, Expression::False) } else { (Expression::False, Expression::True) };
+    let mut flattened: Vec<Expression> = Vec::new();
+    for operand in operands {
+        let nested = match operand {
+            Expression::And(nested) if is_and => nested,
+            Expression::Or(nested) if !is_and => nested,
+            operand => vec![operand],
+        };
+        for operand in nested {
+            if operand == absorbing {
+                return absorbing;
+            }
+            if operand != unit && !flattened.contains(&operand) {
+                flattened.push(operand);
+            }
+        }
+    }
+    match flattened.len() {
+        0 => unit,
+        1 => flattened.pop().unwrap(),
+        _ if is_and => Expression::And(flattened),
+        _ => Expression::Or(flattened),
+    }
+}
+
+/**
+ * Replace variable by value in expression
+ */
+fn substitute(expression: &Expression, variable: usize, value: &Expression) -> Expression {
+    match expression {
+        Expression::Variable(other) if *other == variable => value.clone(),
+        Expression::And(operands) => simplify(Expression::And(operands.iter().map(|operand| substitute(operand, variable, value)).collect())),
+        Expression::Or(operands) => simplify(Expression::Or(operands.iter().map(|operand| substitute(operand, variable, value)).collect())),
+        _ => expression.clone(),
+    }
+}
+
+fn find_variables(expression: &Expression, variables: &mut Vec<usize>) {
+    match expression {
+        Expression::Variable(variable) => variables.push(*variable),
+        Expression::And(operands) | Expression::Or(operands) => {
+            for operand in operands {
+                find_variables(operand, variables);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn evaluate(expression: &Expression, values: &dyn Fn(usize) -> bool) -> bool {
+    match expression {
+        Expression::True => true,
+        Expression::False => false,
+        Expression::Variable(variable) => values(*variable),
+        Expression::And(operands) => operands.iter().all(|operand| evaluate(operand, values)),
+        Expression::Or(operands) => operands.iter().any(|operand| evaluate(operand, values)),
+    }
+}
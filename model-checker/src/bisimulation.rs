@@ -0,0 +1,117 @@
+use crate::types::formula::{find_actions, Node};
+use crate::types::ltl::Ltl;
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+
+/**
+ * Given an LTL instance and the label of its internal action, minimise the instance modulo branching bisimulation.
+ * Returns the quotient LTS, together with for every state of `instance` the state of the quotient it is mapped to.
+ *
+ * States on a cycle of internal transitions are always branching bisimilar, so we first collapse the strongly connected
+ * components of the internal transitions. What remains has no internal cycles, which lets us refine the partition with
+ * signatures (Blom & Orzan), computing the same partition as the Groote-Vaandrager algorithm: the signature of a state s
+ * is the set of pairs (a, B) such that s can reach, using only inert internal steps (that stay in the block of s),
+ * a state with an a-transition into block B that is not itself inert.
+ * We split blocks by signature until the number of blocks no longer grows.
+ */
+pub fn branching_bisimulation_reduction(instance: &Ltl, internal_action: &str) -> (Ltl, Vec<i64>) {
+    // Collapse the internal cycles, components are numbered such that internal transitions go to lower indices
+    let (component, nr_of_components) = instance.get_strongly_connected_components(Some(internal_action));
+    let nr_of_components = nr_of_components as usize;
+
+    // Number the labels, so signatures can be compared cheaply
+    let mut labels: Vec<String> = instance.get_labels().into_iter().collect();
+    labels.sort();
+    let label_index: HashMap<&String, usize> = labels.iter().enumerate().map(|(i, l)| (l, i)).collect();
+    let internal: Option<usize> = labels.iter().position(|l| l == internal_action);
+
+    // Build the transitions between the components, dropping the internal steps within a component
+    let mut edges: Vec<HashSet<(usize, usize)>> = vec![HashSet::new(); nr_of_components];
+    for (state, state_map) in &instance.transitions {
+        let source = component[*state as usize] as usize;
+        for (label, targets) in state_map {
+            let a = label_index[label];
+            for target in targets {
+                let target = component[*target as usize] as usize;
+                if Some(a) == internal && source == target {
+                    continue;
+                }
+                edges[source].insert((a, target));
+            }
+        }
+    }
+
+    // Start with a single block and refine until stable
+    let mut block: Vec<usize> = vec![0; nr_of_components];
+    let mut nr_of_blocks: usize = 1;
+    loop {
+        let mut signatures: Vec<BTreeSet<(usize, usize)>> = vec![BTreeSet::new(); nr_of_components];
+        let mut new_blocks: HashMap<(usize, BTreeSet<(usize, usize)>), usize> = HashMap::new();
+        let mut new_block: Vec<usize> = vec![0; nr_of_components];
+
+        // Internal successors have a lower index, so their signature is known when we need it
+        for c in 0..nr_of_components {
+            let mut signature: BTreeSet<(usize, usize)> = BTreeSet::new();
+            for (a, target) in &edges[c] {
+                if Some(*a) == internal && block[*target] == block[c] {
+                    // inert internal step, inherit everything the target can do
+                    signature.extend(signatures[*target].iter().cloned());
+                } else {
+                    signature.insert((*a, block[*target]));
+                }
+            }
+            let key = (block[c], signature.clone());
+            let next_id = new_blocks.len();
+            new_block[c] = *new_blocks.entry(key).or_insert(next_id);
+            signatures[c] = signature;
+        }
+
+        // A block can only be split, so if the number of blocks did not change, neither did the partition
+        let stable = new_blocks.len() == nr_of_blocks;
+        nr_of_blocks = new_blocks.len();
+        block = new_block;
+        if stable {
+            break;
+        }
+    }
+
+    // Number the blocks in the order of the smallest state they contain, so the quotient does not depend on hashing
+    let mut renumber: HashMap<usize, i64> = HashMap::new();
+    let mut state_map: Vec<i64> = Vec::with_capacity(instance.nr_of_states as usize);
+    for state in 0..instance.nr_of_states as usize {
+        let b = block[component[state] as usize];
+        let next_id = renumber.len() as i64;
+        state_map.push(*renumber.entry(b).or_insert(next_id));
+    }
+
+    let mut quotient = Ltl::new(state_map[instance.first_state as usize], 0, nr_of_blocks as i64);
+    for (state, state_map_out) in &instance.transitions {
+        let source = state_map[*state as usize];
+        for (label, targets) in state_map_out {
+            for target in targets {
+                let target = state_map[*target as usize];
+                if label == internal_action && source == target {
+                    continue;
+                }
+                quotient.add_transition(source, label, target, false);
+            }
+        }
+    }
+
+    (quotient, state_map)
+}
+
+/**
+ * Check whether the result of the formula given by node is the same on the instance and on its branching bisimulation reduction.
+ * Modalities see every single step, so with internal transitions even `<a>true` can change, e.g. 0 -i-> 1 -a-> 2 is reduced to
+ * 0 -a-> 1, and internal cycles, i.e. divergence, are removed. So this only holds if the instance has no internal transitions,
+ * in which case branching bisimulation is strong bisimulation, or if the formula has no modalities.
+ */
+pub fn preserves_formula(instance: &Ltl, node: &Node, internal_action: &str) -> bool {
+    let internal_transitions = instance.transitions.values()
+        .any(|state_map| state_map.get(internal_action).is_some_and(|targets| !targets.is_empty()));
+    !internal_transitions || find_actions(node).is_empty()
+}
@@ -0,0 +1,108 @@
+use crate::types::ltl::Ltl;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+
+/**
+ * Given a list of LTL components and for each component its synchronisation set, build their CSP-style parallel composition.
+ *
+ * A label a in the synchronisation set of component i can only be done by component i together with all other components
+ * that have a in their synchronisation set (multi-party synchronisation), all of them doing an a-transition at the same time.
+ * Labels that are not in the synchronisation set of a component are done by that component on its own (interleaving).
+ * The internal action `internal_action` never synchronises.
+ *
+ * Only the states reachable from the tuple of initial states are built, the composed initial state is state 0. Labels and
+ *   targets are explored in sorted order, so the same components always give the same numbering.
+ */
+pub fn compose(components: &[Ltl], sync: &[HashSet<String>], internal_action: &str) -> Ltl {
+    if components.len() != sync.len() {
+        panic!("Expected a synchronisation set for each of the {} components, got {}", components.len(), sync.len());
+    }
+
+    // For each label that is synchronised on, the components that have to take part
+    let mut participants: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, labels) in sync.iter().enumerate() {
+        for label in labels {
+            if label != internal_action {
+                participants.entry(label.as_str()).or_default().push(i);
+            }
+        }
+    }
+
+    // Explore the product breadth first, numbering the states in the order we find them
+    let initial: Vec<i64> = components.iter().map(|c| c.first_state).collect();
+    let mut state_ids: HashMap<Vec<i64>, i64> = HashMap::from([(initial.clone(), 0)]);
+    let mut queue: VecDeque<Vec<i64>> = VecDeque::from([initial]);
+    let mut transitions: Vec<(i64, String, i64)> = Vec::new();
+
+    while let Some(state) = queue.pop_front() {
+        let source = state_ids[&state];
+        let mut successors: Vec<(String, Vec<i64>)> = Vec::new();
+
+        // Local steps: a component does a label that it does not synchronise on
+        for (i, component) in components.iter().enumerate() {
+            let local: BTreeMap<&String, &HashSet<i64>> = component.transitions.get(&state[i]).into_iter().flatten().collect();
+            for (label, targets) in local {
+                if label != internal_action && sync[i].contains(label) {
+                    continue;
+                }
+                for target in sorted(targets) {
+                    let mut next = state.clone();
+                    next[i] = target;
+                    successors.push((label.clone(), next));
+                }
+            }
+        }
+
+        // Synchronised steps: all participants do the label together
+        for (label, members) in &participants {
+            let mut nexts: Vec<Vec<i64>> = vec![state.clone()];
+            for i in members {
+                let targets = match components[*i].transitions.get(&state[*i]).and_then(|m| m.get(*label)) {
+                    Some(targets) => targets,
+                    None => {
+                        // one participant cannot do the label, so neither can the composition
+                        nexts.clear();
+                        break;
+                    }
+                };
+                let targets = sorted(targets);
+                nexts = nexts.iter()
+                    .flat_map(|next| targets.iter().map(move |target| {
+                        let mut next = next.clone();
+                        next[*i] = *target;
+                        next
+                    }))
+                    .collect();
+            }
+            successors.extend(nexts.into_iter().map(|next| (label.to_string(), next)));
+        }
+
+        for (label, next) in successors {
+            let next_id = state_ids.len() as i64;
+            let target = *state_ids.entry(next.clone()).or_insert_with(|| {
+                queue.push_back(next);
+                next_id
+            });
+            transitions.push((source, label, target));
+        }
+    }
+
+    let mut ltl = Ltl::new(0, transitions.len() as i64, state_ids.len() as i64);
+    for (source, label, target) in transitions {
+        ltl.add_transition(source, &label, target, false);
+    }
+    ltl
+}
+
+/**
+ * The targets of a transition in increasing order
+ */
+fn sorted(targets: &HashSet<i64>) -> Vec<i64> {
+    let mut targets: Vec<i64> = targets.iter().cloned().collect();
+    targets.sort();
+    targets
+}
@@ -0,0 +1,41 @@
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+
+/**
+ * Open the file at `file_path` for reading line by line. Files compressed with gzip or bzip2 are recognised by their
+ * magic bytes and decompressed while reading, so they never have to be unpacked to disk.
+ */
+pub fn open_file(file_path: &Path) -> Box<dyn BufRead> {
+    let file = File::open(file_path)
+        .unwrap_or_else(|e| panic!("Should have been able to read the file {:?}: {}", file_path, e));
+    let mut reader = BufReader::new(file);
+
+    // Peek at the first bytes without consuming them
+    let magic: Vec<u8> = reader.fill_buf()
+        .unwrap_or_else(|e| panic!("Should have been able to read the file {:?}: {}", file_path, e))
+        .iter().take(3).cloned().collect();
+
+    if magic.starts_with(&[0x1f, 0x8b]) {
+        Box::new(BufReader::new(MultiGzDecoder::new(reader)))
+    } else if magic.starts_with(b"BZh") {
+        Box::new(BufReader::new(MultiBzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    }
+}
+
+/**
+ * Remove a compression extension (`.gz`, `.gzip` or `.bz2`) from `file_path`, e.g. `dining_2.aut.gz` becomes `dining_2.aut`,
+ * so the type of the file can be checked on what remains.
+ */
+pub fn strip_compression_extension(file_path: &Path) -> PathBuf {
+    match file_path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz") | Some("gzip") | Some("bz2") => file_path.with_extension(""),
+        _ => file_path.to_path_buf(),
+    }
+}
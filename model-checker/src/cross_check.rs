@@ -0,0 +1,131 @@
+use crate::alternation_free::execute_alternation_free;
+use crate::bes::execute_gauss;
+use crate::local::execute_local;
+use crate::solver::{execute, execute_improved};
+use crate::symbolic::execute_symbolic;
+use crate::types::formula::{Formula, Node, find_free_variables, formula_to_string};
+use crate::types::transition_system::TransitionSystem;
+
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+
+
+// Specify custom type `Disagreement`
+// the smallest subformula on which the algorithms found different sets of states, with the set of every algorithm
+
+#[derive(Debug, PartialEq)]
+pub struct Disagreement {
+    pub subformula: String,
+    pub results: Vec<(&'static str, BTreeSet<i64>)>,
+    pub states: BTreeSet<i64>,
+}
+
+// Specify custom type `Algorithm`
+// an algorithm that cross_check compares: its name, and how it calculates the set of states that satisfy a subformula,
+// or None if it does not apply to the subformula or the instance
+
+#[derive(Clone, Copy)]
+pub struct Algorithm {
+    pub name: &'static str,
+    pub run: fn(&Node, &dyn TransitionSystem) -> Option<HashSet<i64>>,
+}
+
+// Above this many states local, which runs once for every state, and Gauss elimination take minutes
+const MAX_STATES_LOCAL_GAUSS: usize = 500;
+
+/**
+ * Get the algorithms cross_check compares: naive, Emerson-Lei, local (for every state), Gauss elimination, the symbolic
+ *   algorithm and, if the formula has no alternating fixed points, the alternation-free algorithm. Local and Gauss elimination are left out on instances
+ *   with more than 500 states.
+ */
+pub fn algorithms() -> Vec<Algorithm> {
+    vec![
+        Algorithm { name: "naive", run: |node, instance| Some(execute(to_formula(node), instance).0) },
+        Algorithm { name: "improved", run: |node, instance| Some(execute_improved(to_formula(node), instance).0) },
+        Algorithm { name: "local", run: |node, instance| {
+            let states = instance.states();
+            (states.len() <= MAX_STATES_LOCAL_GAUSS).then(|| states.into_iter()
+                .filter(|state| execute_local(to_formula(node), instance, *state).0)
+                .collect())
+        } },
+        Algorithm { name: "gauss", run: |node, instance| {
+            (instance.states().len() <= MAX_STATES_LOCAL_GAUSS).then(|| execute_gauss(to_formula(node), instance).0)
+        } },
+        Algorithm { name: "symbolic", run: |node, instance| Some(execute_symbolic(to_formula(node), instance).0) },
+        Algorithm { name: "alt-free", run: |node, instance| execute_alternation_free(&to_formula(node), instance).map(|(result, _)| result) },
+    ]
+}
+
+/**
+ * Make a Formula of node, for the algorithms that take one
+ */
+fn to_formula(node: &Node) -> Formula {
+    Formula { root_node: node.clone() }
+}
+
+/**
+ * Check a Formula f on an LTL instance with every algorithm of `algorithms()`.
+ *   Returns the set they agree on, or the smallest subformula on which they disagree.
+ */
+pub fn cross_check<T: TransitionSystem>(f: &Formula, instance: &T) -> Result<BTreeSet<i64>, Disagreement> {
+    cross_check_with(f, instance, &algorithms())
+}
+
+/**
+ * Check a Formula f on an LTL instance with the given algorithms, and return the set they agree on, or the smallest
+ *   subformula on which they disagree. Algorithms that do not apply are left out.
+ *
+ * Only subformulas that do not depend on the fixed points around them can be checked on their own, so these are tried from
+ * the smallest to the largest. The states involved are those on which not all algorithms agree.
+ */
+pub fn cross_check_with<T: TransitionSystem>(f: &Formula, instance: &T, algorithms: &[Algorithm]) -> Result<BTreeSet<i64>, Disagreement> {
+    let run_all = |node: &Node| run_all(node, instance, algorithms);
+    let results = run_all(&f.root_node);
+    if results.iter().all(|(_, result)| *result == results[0].1) {
+        return Ok(results[0].1.clone());
+    }
+
+    // Variables that are not bound anywhere are the empty set, so subformulas that only use those are closed as well
+    let unbound: HashSet<String> = find_free_variables(&f.root_node);
+    let mut subformulas: Vec<&Node> = Vec::new();
+    find_closed_subformulas(&f.root_node, &unbound, &mut subformulas);
+    for subformula in subformulas {
+        let results = if std::ptr::eq(subformula, &f.root_node) { results.clone() } else { run_all(subformula) };
+        let union: BTreeSet<i64> = results.iter().flat_map(|(_, result)| result.iter().cloned()).collect();
+        let states: BTreeSet<i64> = union.into_iter()
+            .filter(|state| !results.iter().all(|(_, result)| result.contains(state)))
+            .collect();
+        if !states.is_empty() {
+            return Err(Disagreement { subformula: formula_to_string(subformula), results, states });
+        }
+    }
+    unreachable!("The algorithms should disagree on the formula itself")
+}
+
+/**
+ * Calculate the set of states that satisfy node with every algorithm that applies
+ */
+fn run_all(node: &Node, instance: &dyn TransitionSystem, algorithms: &[Algorithm]) -> Vec<(&'static str, BTreeSet<i64>)> {
+    algorithms.iter()
+        .filter_map(|algorithm| (algorithm.run)(node, instance).map(|result| (algorithm.name, result.into_iter().collect())))
+        .collect()
+}
+
+/**
+ * Find the subformulas of node whose free variables are all in `unbound`, in postorder, so every subformula comes after its
+ *   own subformulas. Actions are not subformulas.
+ */
+fn find_closed_subformulas<'a>(node: &'a Node, unbound: &HashSet<String>, subformulas: &mut Vec<&'a Node>) {
+    match node {
+        Node::BinaryExpr { op: _, lhs, rhs } => {
+            find_closed_subformulas(lhs, unbound, subformulas);
+            find_closed_subformulas(rhs, unbound, subformulas);
+        }
+        Node::FixPointExpr { op: _, variable: _, rhs, surrounding_binder: _ } => find_closed_subformulas(rhs, unbound, subformulas),
+        Node::Action(_) => return,
+        _ => {}
+    }
+    if find_free_variables(node).is_subset(unbound) {
+        subformulas.push(node);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::types::ltl::Ltl;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+
+// Specify custom type `Diagnostics`
+// quick facts about an LTS, to know what we are dealing with before writing properties
+
+pub struct Diagnostics {
+    pub nr_of_states: i64,
+    pub nr_of_transitions: i64,
+    pub deadlock_states: Vec<i64>, // states without outgoing transitions
+    pub label_histogram: Vec<(String, i64)>, // label -> number of transitions, most frequent first
+    pub max_out_degree: i64,
+    pub max_out_degree_state: i64,
+    pub nr_of_sccs: i64,
+    pub largest_scc: i64,
+    pub bottom_sccs: Vec<Vec<i64>>, // components that cannot be left, sorted on their smallest state
+    pub divergent_states: Vec<i64>, // states on a cycle of internal transitions
+}
+
+/**
+ * Given an LTL instance and the label of its internal action, compute its diagnostics.
+ */
+pub fn diagnose(instance: &Ltl, internal_action: &str) -> Diagnostics {
+    let mut nr_of_transitions: i64 = 0;
+    let mut deadlock_states: Vec<i64> = Vec::new();
+    let mut histogram: HashMap<String, i64> = HashMap::new();
+    let mut max_out_degree: i64 = 0;
+    let mut max_out_degree_state: i64 = instance.first_state;
+
+    for state in 0..instance.nr_of_states {
+        let mut out_degree: i64 = 0;
+        if let Some(state_map) = instance.transitions.get(&state) {
+            for (label, targets) in state_map {
+                *histogram.entry(label.clone()).or_insert(0) += targets.len() as i64;
+                out_degree += targets.len() as i64;
+            }
+        }
+        if out_degree == 0 {
+            deadlock_states.push(state);
+        }
+        if out_degree > max_out_degree {
+            max_out_degree = out_degree;
+            max_out_degree_state = state;
+        }
+        nr_of_transitions += out_degree;
+    }
+
+    let mut label_histogram: Vec<(String, i64)> = histogram.into_iter().collect();
+    label_histogram.sort_by(|(l1, c1), (l2, c2)| c2.cmp(c1).then(l1.cmp(l2)));
+
+    // Group the states by strongly connected component, a component is bottom if no transition leaves it
+    let (component, nr_of_sccs) = instance.get_strongly_connected_components(None);
+    let mut members: Vec<Vec<i64>> = vec![Vec::new(); nr_of_sccs as usize];
+    for state in 0..instance.nr_of_states {
+        members[component[state as usize] as usize].push(state);
+    }
+    let mut is_bottom: Vec<bool> = vec![true; nr_of_sccs as usize];
+    for (state, state_map) in &instance.transitions {
+        let source = component[*state as usize];
+        if state_map.values().flatten().any(|target| component[*target as usize] != source) {
+            is_bottom[source as usize] = false;
+        }
+    }
+    let largest_scc = members.iter().map(|m| m.len() as i64).max().unwrap_or(0);
+    let mut bottom_sccs: Vec<Vec<i64>> = members.into_iter()
+        .enumerate()
+        .filter(|(c, _)| is_bottom[*c])
+        .map(|(_, m)| m)
+        .collect();
+    bottom_sccs.sort();
+
+    // A state diverges if it is on a cycle of internal transitions, i.e. in a non-trivial internal component or on an internal self loop
+    let (internal_component, nr_of_internal_sccs) = instance.get_strongly_connected_components(Some(internal_action));
+    let mut internal_sizes: Vec<i64> = vec![0; nr_of_internal_sccs as usize];
+    for c in &internal_component {
+        internal_sizes[*c as usize] += 1;
+    }
+    let divergent_states: Vec<i64> = (0..instance.nr_of_states)
+        .filter(|state| {
+            internal_sizes[internal_component[*state as usize] as usize] > 1
+                || instance.transitions.get(state)
+                    .and_then(|m| m.get(internal_action))
+                    .is_some_and(|targets| targets.contains(state))
+        })
+        .collect();
+
+    Diagnostics {
+        nr_of_states: instance.nr_of_states,
+        nr_of_transitions,
+        deadlock_states,
+        label_histogram,
+        max_out_degree,
+        max_out_degree_state,
+        nr_of_sccs,
+        largest_scc,
+        bottom_sccs,
+        divergent_states,
+    }
+}
+
+/**
+ * Given the header `des (first_state, nr_of_transitions, nr_of_states)` of an .aut file and the transitions in its body,
+ * return a description of every way in which they do not match.
+ */
+pub fn check_aut_header(header: (i64, i64, i64), transitions: &[(i64, String, i64)]) -> Vec<String> {
+    let (first_state, nr_of_transitions, nr_of_states) = header;
+    let mut mismatches: Vec<String> = Vec::new();
+
+    if first_state < 0 || first_state >= nr_of_states {
+        mismatches.push(format!("the initial state {} is not one of the {} states", first_state, nr_of_states));
+    }
+    if transitions.len() as i64 != nr_of_transitions {
+        mismatches.push(format!("the header declares {} transitions, but the body has {}", nr_of_transitions, transitions.len()));
+    }
+
+    let mut seen: HashSet<&(i64, String, i64)> = HashSet::new();
+    let mut duplicates: i64 = 0;
+    for transition in transitions {
+        let (start_state, label, end_state) = transition;
+        for state in [start_state, end_state] {
+            if *state < 0 || *state >= nr_of_states {
+                mismatches.push(format!("transition ({},\"{}\",{}) uses state {}, but the header declares {} states",
+                    start_state, label, end_state, state, nr_of_states));
+            }
+        }
+        if !seen.insert(transition) {
+            duplicates += 1;
+        }
+    }
+    if duplicates > 0 {
+        mismatches.push(format!("the body contains {} duplicate transitions", duplicates));
+    }
+
+    let max_state = transitions.iter().map(|(s, _, t)| *s.max(t)).max().unwrap_or(first_state).max(first_state);
+    if max_state + 1 < nr_of_states {
+        mismatches.push(format!("the header declares {} states, but the largest state used is {}", nr_of_states, max_state));
+    }
+
+    mismatches
+}
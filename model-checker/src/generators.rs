@@ -0,0 +1,176 @@
+use crate::types::ltl::Ltl;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+
+/**
+ * Build the LTS of n dining philosophers sitting at a round table, like `input/part2-1/dining_n.aut`.
+ *
+ * Philosopher k takes fork k (left) and then fork k+1 (right), eats, and puts back first the left and then the right fork.
+ * Philosopher 0 is Plato: his eating is labelled `plato`, the eating of all others is labelled `others`.
+ * Taking and putting back forks is internal (`i`). The system deadlocks when everybody holds their left fork.
+ */
+pub fn dining_philosophers(n: usize) -> Ltl {
+    if n < 2 {
+        panic!("Expected at least 2 philosophers, got {}", n);
+    }
+    // The local state of a philosopher: 0 thinking, 1 has left fork, 2 has both forks, 3 has eaten, 4 has right fork
+    let holds_left = |local: u8| (1..=3).contains(&local);
+    let holds_right = |local: u8| (2..=4).contains(&local);
+
+    explore(vec![0u8; n], |state: &Vec<u8>| {
+        let mut successors: Vec<(String, Vec<u8>)> = Vec::new();
+        // Let the others move before Plato
+        for k in (1..n).chain(0..1) {
+            // Fork k is the left fork of philosopher k and the right fork of philosopher k-1
+            let left_free = !holds_right(state[(k + n - 1) % n]);
+            let right_free = !holds_left(state[(k + 1) % n]);
+            let (label, next) = match state[k] {
+                0 if left_free => ("i", 1),
+                1 if right_free => ("i", 2),
+                2 if k == 0 => ("plato", 3),
+                2 => ("others", 3),
+                3 => ("i", 4),
+                4 => ("i", 0),
+                _ => continue,
+            };
+            let mut target = state.clone();
+            target[k] = next;
+            successors.push((label.to_string(), target));
+        }
+        successors
+    })
+}
+
+/**
+ * Build the LTS of n demanding children with one parent, like `input/part2-2/demanding_children_n.aut`.
+ *
+ * Every child is playing, then asks a question, then receives wisdom from the parent and then goes back to playing.
+ * The parent answers one child at a time, so a child can only receive wisdom if no other child is receiving it.
+ * The actions of child 0 are `ask`, `wisdom` and `playing`, the actions of all other children are internal (`i`).
+ */
+pub fn demanding_children(n: usize) -> Ltl {
+    if n < 1 {
+        panic!("Expected at least 1 child, got {}", n);
+    }
+    // The local state of a child: 0 playing, 1 questioning, 2 receiving wisdom
+    explore(vec![0u8; n], |state: &Vec<u8>| {
+        let someone_receives = state.contains(&2);
+        let mut successors: Vec<(String, Vec<u8>)> = Vec::new();
+        // Let the other children move before child 0
+        for k in (1..n).chain(0..1) {
+            let (label, next) = match state[k] {
+                0 => ("ask", 1),
+                1 if !someone_receives => ("wisdom", 2),
+                2 => ("playing", 0),
+                _ => continue,
+            };
+            let mut target = state.clone();
+            target[k] = next;
+            successors.push((if k == 0 { label } else { "i" }.to_string(), target));
+        }
+        successors
+    })
+}
+
+/**
+ * Build the LTS of a token ring of n processes that protects a critical section.
+ *
+ * Every process can `request(k)` access at any time. The process holding the token `enter(k)`s the critical section if it
+ * requested access and `leave(k)`s it again afterwards, otherwise it passes the token to the next process (internal, `i`).
+ */
+pub fn mutex_ring(n: usize) -> Ltl {
+    if n < 1 {
+        panic!("Expected at least 1 process, got {}", n);
+    }
+    // The token position and the local state of every process: 0 idle, 1 requested access, 2 in the critical section
+    explore((0usize, vec![0u8; n]), |(token, state): &(usize, Vec<u8>)| {
+        let mut successors: Vec<(String, (usize, Vec<u8>))> = Vec::new();
+        for k in 0..n {
+            if state[k] == 0 {
+                let mut target = state.clone();
+                target[k] = 1;
+                successors.push((format!("request({})", k), (*token, target)));
+            }
+        }
+        let mut target = state.clone();
+        match state[*token] {
+            0 => {
+                successors.push((String::from("i"), ((*token + 1) % n, target)));
+            }
+            1 => {
+                target[*token] = 2;
+                successors.push((format!("enter({})", token), (*token, target)));
+            }
+            _ => {
+                target[*token] = 0;
+                successors.push((format!("leave({})", token), ((*token + 1) % n, target)));
+            }
+        }
+        successors
+    })
+}
+
+/**
+ * Build a random LTS with n states, in which every state has `branching` outgoing transitions labelled with one of
+ * `nr_of_labels` labels `a0`, `a1`, ... The same seed gives the same LTS.
+ *
+ * Every state other than the initial state 0 gets one of its incoming transitions from a smaller state,
+ * so all states are reachable.
+ */
+pub fn random_lts(n: usize, branching: usize, nr_of_labels: usize, seed: u64) -> Ltl {
+    if n < 1 || nr_of_labels < 1 {
+        panic!("Expected at least 1 state and 1 label, got {} states and {} labels", n, nr_of_labels);
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut transitions: Vec<(i64, String, i64)> = Vec::new();
+
+    for target in 1..n {
+        let source = rng.gen_range(0..target);
+        transitions.push((source as i64, format!("a{}", rng.gen_range(0..nr_of_labels)), target as i64));
+    }
+    for source in 0..n {
+        for _ in 0..branching {
+            let target = rng.gen_range(0..n);
+            transitions.push((source as i64, format!("a{}", rng.gen_range(0..nr_of_labels)), target as i64));
+        }
+    }
+
+    let mut ltl = Ltl::new(0, transitions.len() as i64, n as i64);
+    for (source, label, target) in transitions {
+        ltl.add_transition(source, &label, target, false);
+    }
+    ltl
+}
+
+/**
+ * Build the LTS of all states reachable from `initial`, where `successors` gives the outgoing transitions (label, target)
+ * of a state. States are numbered breadth first, in the order `successors` returns them, the initial state is state 0.
+ */
+fn explore<S: Clone + Eq + Hash, F: Fn(&S) -> Vec<(String, S)>>(initial: S, successors: F) -> Ltl {
+    let mut state_ids: HashMap<S, i64> = HashMap::from([(initial.clone(), 0)]);
+    let mut queue: VecDeque<S> = VecDeque::from([initial]);
+    let mut transitions: Vec<(i64, String, i64)> = Vec::new();
+
+    while let Some(state) = queue.pop_front() {
+        let source = state_ids[&state];
+        for (label, next) in successors(&state) {
+            let nr_of_states = state_ids.len() as i64;
+            let target = *state_ids.entry(next.clone()).or_insert_with(|| {
+                queue.push_back(next);
+                nr_of_states
+            });
+            transitions.push((source, label, target));
+        }
+    }
+
+    let mut ltl = Ltl::new(0, transitions.len() as i64, state_ids.len() as i64);
+    for (source, label, target) in transitions {
+        ltl.add_transition(source, &label, target, false);
+    }
+    ltl
+}
@@ -0,0 +1,117 @@
+use crate::solver::{execute_from, find_fixpoints, Options, Statistics};
+use crate::types::formula::{Formula, Node, Operator};
+use crate::types::ltl::Ltl;
+use crate::types::transition_system::TransitionSystem;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+
+// Specify custom type `Change`
+// an edit of an LTS: a transition (source, label, target) that is added or removed
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Add(i64, String, i64),
+    Remove(i64, String, i64),
+}
+
+/**
+ * Apply the changes to the ltl in order, and return the changes that did change it,
+ *   i.e. without adding transitions that were already there or removing transitions that were not
+ */
+pub fn apply_changes(ltl: &mut Ltl, changes: &[Change]) -> Vec<Change> {
+    let mut applied: Vec<Change> = Vec::new();
+    for change in changes {
+        let changed = match change {
+            Change::Add(source, label, target) => {
+                let new = !ltl.successors(*source, label).contains(target);
+                ltl.add_transition(*source, label, *target, false);
+                new
+            }
+            Change::Remove(source, label, target) => ltl.remove_transition(*source, label, *target, false),
+        };
+        if changed {
+            applied.push(change.clone());
+        }
+    }
+    applied
+}
+
+// Specify custom type `CheckedFormula`
+// a formula that was checked on an LTS, with the last value of each of its fixed points, so it can be checked again after the
+// LTS changed without starting from scratch. There is no negation, so if the changes only add transitions with labels that
+// occur in diamonds and remove transitions with labels that occur in boxes, every subformula holds in at least the states it
+// held in before. Then the old value of a least fixed point is below its new value, also for every value the fixed points around
+// it take on the way, so it can start from there. In the opposite case the same holds for greatest fixed points.
+
+pub struct CheckedFormula {
+    formula: Formula,
+    values: HashMap<usize, HashSet<i64>>,
+    pub result: HashSet<i64>,
+    pub statistics: Statistics,
+}
+
+impl CheckedFormula {
+    /**
+     * Check f on the instance from scratch with the naive algorithm
+     */
+    pub fn check<T: TransitionSystem + ?Sized>(f: Formula, instance: &T) -> Self {
+        let (result, statistics, values) = execute_from(&f, instance, &Options::default(), &HashMap::new(), &mut HashMap::new());
+        CheckedFormula { formula: f, values, result, statistics }
+    }
+
+    /**
+     * Check the formula again on the instance after the changes, as returned by apply_changes. The fixed points that the changes
+     *   can only make larger (least fixed points) or smaller (greatest fixed points) start from their old values, the others from
+     *   scratch. Returns the new result, the statistics are those of this check.
+     */
+    pub fn recheck<T: TransitionSystem + ?Sized>(&mut self, instance: &T, changes: &[Change]) -> &HashSet<i64> {
+        let (grows, shrinks) = find_directions(&self.formula.root_node, changes);
+        if grows && shrinks {
+            // No modality has a label that changed
+            self.statistics = Statistics::default();
+            return &self.result;
+        }
+        let warm = if grows { Some(Operator::LeastFixpoint) } else if shrinks { Some(Operator::GreatestFixpoint) } else { None };
+        let start: HashMap<usize, HashSet<i64>> = find_fixpoints(&self.formula.root_node).into_iter()
+            .enumerate()
+            .filter(|(_, fixpoint)| matches!(fixpoint, Node::FixPointExpr { op, .. } if Some(op) == warm.as_ref()))
+            .filter_map(|(index, _)| self.values.get(&index).map(|value| (index, value.clone())))
+            .collect();
+        let (result, statistics, values) = execute_from(&self.formula, instance, &Options::default(), &start, &mut HashMap::new());
+        self.result = result;
+        self.statistics = statistics;
+        self.values = values;
+        &self.result
+    }
+}
+
+/**
+ * Find whether the changes can only make the subformulas of node hold in more states, and whether they can only make them
+ *   hold in fewer states. Both are true if no modality has the label of a change.
+ */
+fn find_directions(node: &Node, changes: &[Change]) -> (bool, bool) {
+    match node {
+        Node::BinaryExpr { op, lhs, rhs } => {
+            let (grows_lhs, shrinks_lhs) = find_directions(lhs, changes);
+            let (grows_rhs, shrinks_rhs) = find_directions(rhs, changes);
+            let (mut grows, mut shrinks) = (grows_lhs && grows_rhs, shrinks_lhs && shrinks_rhs);
+            if let Node::Action(label) = &**lhs {
+                for change in changes {
+                    // Adding a transition makes a diamond hold in more states and a box in fewer, removing one the opposite
+                    let (added, changed_label) = match change {
+                        Change::Add(_, changed_label, _) => (true, changed_label),
+                        Change::Remove(_, changed_label, _) => (false, changed_label),
+                    };
+                    if changed_label == label {
+                        if added == (*op == Operator::DiamondModality) { shrinks = false } else { grows = false }
+                    }
+                }
+            }
+            (grows, shrinks)
+        }
+        Node::FixPointExpr { op: _, variable: _, rhs, surrounding_binder: _ } => find_directions(rhs, changes),
+        Node::Variable(_) | Node::Action(_) | Node::UnaryExpr { op: _ } => (true, true),
+    }
+}
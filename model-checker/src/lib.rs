@@ -1 +1,20 @@
-pub mod types;
\ No newline at end of file
+pub mod types;
+pub mod solver;
+pub mod bisimulation;
+pub mod composition;
+pub mod diagnostics;
+pub mod compression;
+pub mod aut;
+pub mod simulator;
+pub mod refinement;
+pub mod generators;
+pub mod witness;
+pub mod local;
+pub mod parity_game;
+pub mod bes;
+pub mod cross_check;
+pub mod bdd;
+pub mod symbolic;
+pub mod incremental;
+pub mod batch;
+pub mod alternation_free;
@@ -0,0 +1,235 @@
+use crate::solver::execute;
+use crate::types::formula::{Formula, Node, Operator, find_free_variables};
+use crate::types::transition_system::TransitionSystem;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+
+// A subformula with its variables replaced by (the index of) their binder, so the formula becomes a graph
+enum Equation {
+    True,
+    False,
+    And(usize, usize),
+    Or(usize, usize),
+    Diamond(String, usize),
+    Box(String, usize),
+    Fixpoint(usize), // the body of a fixed point that is solved in the same block
+    Global(Node),    // a closed subformula with alternating fixed points, which we solve globally with the naive algorithm
+}
+
+// A block of equations of the same fixed point that we solve together, with the Liu-Smolka state of its vertices (state, equation)
+struct Block {
+    least: bool,
+    values: HashMap<(i64, usize), bool>,
+    dependents: HashMap<(i64, usize), HashSet<(i64, usize)>>,
+    worklist: VecDeque<(i64, usize)>,
+}
+
+// Specify custom type `LocalSolver`
+// decides for single states whether they satisfy a formula, only exploring the (state, subformula) pairs it needs
+
+struct LocalSolver<'a, T: TransitionSystem + ?Sized> {
+    instance: &'a T,
+    equations: Vec<Equation>,
+    block_of: Vec<usize>,
+    blocks: Vec<Block>,
+    global_values: HashMap<usize, HashSet<i64>>,
+    explored: i64,
+}
+
+/**
+ * Given a Formula f, an LTL instance and a state, decide whether the state satisfies f with a local algorithm.
+ * Also returns the number of (state, subformula) pairs that were explored.
+ *
+ * The formula and the LTL form a boolean equation system with a variable for each (state, subformula) pair, which we
+ * solve on the fly with the algorithm of Liu and Smolka, starting from the pair (state, f). It stops as soon as the value
+ * of that pair is known, e.g. a least fixed point stops when it finds the first state that makes it true.
+ * Every closed fixed point subformula is solved as a separate block, when another block needs its value for some state.
+ * This is only sound without alternation, so closed subformulas with alternating fixed points are solved globally.
+ */
+pub fn execute_local<T: TransitionSystem + ?Sized>(f: Formula, instance: &T, state: i64) -> (bool, i64) {
+    let mut solver = LocalSolver {
+        instance,
+        equations: Vec::new(),
+        block_of: Vec::new(),
+        blocks: Vec::new(),
+        global_values: HashMap::new(),
+        explored: 0,
+    };
+    let root_block = solver.new_block(true);
+    let root = solver.convert(&f.root_node, &HashMap::new(), root_block);
+    let result = solver.query(state, root);
+    (result, solver.explored)
+}
+
+impl<'a, T: TransitionSystem + ?Sized> LocalSolver<'a, T> {
+    fn new_block(&mut self, least: bool) -> usize {
+        self.blocks.push(Block { least, values: HashMap::new(), dependents: HashMap::new(), worklist: VecDeque::new() });
+        self.blocks.len() - 1
+    }
+
+    fn add_equation(&mut self, equation: Equation, block: usize) -> usize {
+        self.equations.push(equation);
+        self.block_of.push(block);
+        self.equations.len() - 1
+    }
+
+    /**
+     * Turn node into equations in block, where scope maps the variables in scope to the equation of their binder.
+     *   Returns the equation of node.
+     */
+    fn convert(&mut self, node: &Node, scope: &HashMap<String, usize>, block: usize) -> usize {
+        match node {
+            // Like the other solvers, a variable that is not bound is the empty set
+            Node::Variable(variable) => match scope.get(variable) {
+                Some(binder) => *binder,
+                None => self.add_equation(Equation::False, block),
+            },
+            Node::UnaryExpr { op } => {
+                let equation = if *op == Operator::SimpleTrue { Equation::True } else { Equation::False };
+                self.add_equation(equation, block)
+            }
+            Node::BinaryExpr { op, lhs, rhs } => {
+                let rhs_equation = self.convert(rhs, scope, block);
+                let equation = match (op, &**lhs) {
+                    (Operator::DiamondModality, Node::Action(label)) => Equation::Diamond(label.clone(), rhs_equation),
+                    (Operator::BoxModality, Node::Action(label)) => Equation::Box(label.clone(), rhs_equation),
+                    (Operator::Conjunction, _) => Equation::And(self.convert(lhs, scope, block), rhs_equation),
+                    (Operator::Disjunction, _) => Equation::Or(self.convert(lhs, scope, block), rhs_equation),
+                    _ => unreachable!("A modality should have an action on its left hand side"),
+                };
+                self.add_equation(equation, block)
+            }
+            Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } => {
+                // A closed fixed point starts a new block, otherwise it is solved together with the fixed points it refers to
+                let block = if find_free_variables(node).iter().any(|free| scope.contains_key(free)) {
+                    block
+                } else if is_alternation_free(node, &HashMap::new()) {
+                    self.new_block(*op == Operator::LeastFixpoint)
+                } else {
+                    return self.add_equation(Equation::Global(node.clone()), block);
+                };
+                // The body is converted after the binder, so the variable can refer to it
+                let binder = self.add_equation(Equation::Fixpoint(0), block);
+                let mut scope = scope.clone();
+                scope.insert(variable.clone(), binder);
+                let body = self.convert(rhs, &scope, block);
+                self.equations[binder] = Equation::Fixpoint(body);
+                binder
+            }
+            Node::Action(_) => unreachable!("An action can only occur in a modality"),
+        }
+    }
+
+    /**
+     * Decide whether state satisfies equation, solving its block until the value of (state, equation) is known
+     */
+    fn query(&mut self, state: i64, equation: usize) -> bool {
+        let block = self.block_of[equation];
+        let vertex = (state, equation);
+        if !self.blocks[block].values.contains_key(&vertex) {
+            let least = self.blocks[block].least;
+            self.blocks[block].values.insert(vertex, !least);
+            self.blocks[block].worklist.push_back(vertex);
+            self.explored += 1;
+        }
+
+        // Values only move away from the initial value of the block, so once a value has moved it is final
+        loop {
+            let least = self.blocks[block].least;
+            if self.blocks[block].values[&vertex] == least {
+                return least;
+            }
+            let Some(next) = self.blocks[block].worklist.pop_front() else {
+                return !least;
+            };
+            let value = self.evaluate(next, block);
+            if value == least && self.blocks[block].values[&next] != least {
+                self.blocks[block].values.insert(next, value);
+                let dependents: Vec<(i64, usize)> = self.blocks[block].dependents.get(&next)
+                    .map(|dependents| dependents.iter().cloned().collect())
+                    .unwrap_or_default();
+                self.blocks[block].worklist.extend(dependents);
+            }
+        }
+    }
+
+    /**
+     * Evaluate the right hand side of vertex in block, with the current values of the vertices it depends on
+     */
+    fn evaluate(&mut self, vertex: (i64, usize), block: usize) -> bool {
+        let (state, equation) = vertex;
+        match &self.equations[equation] {
+            Equation::True => true,
+            Equation::False => false,
+            Equation::And(lhs, rhs) => {
+                let (lhs, rhs) = (*lhs, *rhs);
+                self.get(vertex, state, lhs, block) && self.get(vertex, state, rhs, block)
+            }
+            Equation::Or(lhs, rhs) => {
+                let (lhs, rhs) = (*lhs, *rhs);
+                self.get(vertex, state, lhs, block) || self.get(vertex, state, rhs, block)
+            }
+            Equation::Diamond(label, body) => {
+                let body = *body;
+                let targets = self.instance.successors(state, label);
+                targets.into_iter().any(|target| self.get(vertex, target, body, block))
+            }
+            Equation::Box(label, body) => {
+                let body = *body;
+                let targets = self.instance.successors(state, label);
+                targets.into_iter().all(|target| self.get(vertex, target, body, block))
+            }
+            Equation::Fixpoint(body) => {
+                let body = *body;
+                self.get(vertex, state, body, block)
+            }
+            Equation::Global(node) => {
+                if !self.global_values.contains_key(&equation) {
+                    let (values, _) = execute(Formula { root_node: node.clone() }, self.instance);
+                    self.global_values.insert(equation, values);
+                }
+                self.global_values[&equation].contains(&state)
+            }
+        }
+    }
+
+    /**
+     * Get the current value of (state, equation) for `dependent`, exploring it if it is new.
+     *   A vertex of another block is a closed fixed point, whose final value we query.
+     */
+    fn get(&mut self, dependent: (i64, usize), state: i64, equation: usize, block: usize) -> bool {
+        if self.block_of[equation] != block {
+            return self.query(state, equation);
+        }
+        let vertex = (state, equation);
+        let current = &mut self.blocks[block];
+        current.dependents.entry(vertex).or_default().insert(dependent);
+        if let Some(value) = current.values.get(&vertex) {
+            return *value;
+        }
+        current.values.insert(vertex, !current.least);
+        current.worklist.push_back(vertex);
+        self.explored += 1;
+        !current.least
+    }
+}
+
+/**
+ * Check whether every fixed point in node with free variables has the same type as the fixed points binding them,
+ * where `binders` gives the type of the fixed points binding the variables in scope. Variables that are not bound are ignored.
+ */
+pub fn is_alternation_free(node: &Node, binders: &HashMap<String, Operator>) -> bool {
+    match node {
+        Node::BinaryExpr { op: _, lhs, rhs } => is_alternation_free(lhs, binders) && is_alternation_free(rhs, binders),
+        Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } => {
+            let same_type = find_free_variables(node).iter().all(|free| binders.get(free).is_none_or(|binder| binder == op));
+            let mut binders = binders.clone();
+            binders.insert(variable.clone(), op.clone());
+            same_type && is_alternation_free(rhs, &binders)
+        }
+        _ => true,
+    }
+}
@@ -1,14 +1,46 @@
 use std::fs;
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use std::collections::HashSet;
+use std::time::Duration;
 
 // local imports
 mod solver;
 mod types;
-
-use solver::{execute, execute_improved, find_formula_statistics};
+mod bisimulation;
+mod composition;
+mod diagnostics;
+mod compression;
+mod aut;
+mod simulator;
+mod refinement;
+mod generators;
+mod witness;
+mod local;
+mod parity_game;
+mod bes;
+mod cross_check;
+mod bdd;
+mod symbolic;
+mod alternation_free;
+
+use solver::{execute_with_options, execute_improved_with_options, find_formula_statistics, Options, Trace, TraceRecord, Stop, Approximant};
+use bisimulation::{branching_bisimulation_reduction, preserves_formula};
+use composition::compose;
+use diagnostics::{diagnose, check_aut_header};
+use aut::{parse_aut_file, read_aut_file};
+use simulator::{Simulator, run_simulator};
+use refinement::{check_refinement, RefinementModel, Violation};
+use generators::{dining_philosophers, demanding_children, mutex_ring, random_lts};
+use local::execute_local;
+use parity_game::to_parity_game;
+use cross_check::cross_check;
+use symbolic::execute_symbolic;
+use alternation_free::execute_alternation_free;
+use bes::{Bes, execute_gauss, to_bes, solve_gauss, solve_local};
+use witness::{find_witness, find_counterexample, find_evidence, Witness, Evidence, Step};
 use types::ltl::Ltl;
 use types::formula::Formula;
+use types::label_pattern::LabelPattern;
 // END IMPORT
 
 
@@ -16,20 +48,36 @@ use types::formula::Formula;
 // e.g.: `cargo run -- --aut-file ../input/part2-1/dining_2.aut --mcf-file ../input/part2-1/invariantly_inevitably_eat.mcf --improved`
 //       `main.exe --aut-file ../input/part2-1/dining_2.aut --mcf-file ../input/part2-1/invariantly_inevitably_eat.mcf -i`
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
-    /// The path to the .aut file
-    #[arg(short, long)]
-    aut_file: std::path::PathBuf,
+    /// Instead of checking a formula, run one of the subcommands
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The path to the .aut file, which may be compressed with gzip or bzip2. If given multiple times, the formula is checked on the parallel composition of all files
+    #[arg(short, long, required=true)]
+    aut_file: Vec<std::path::PathBuf>,
 
     /// The path to the .mcf file
-    #[arg(short, long)]
-    mcf_file: std::path::PathBuf,
+    #[arg(short, long, required=true)]
+    mcf_file: Option<std::path::PathBuf>,
 
-    /// Use the improved algorithm, or the regular one
+    /// Use the improved algorithm, or the regular one. Short for `--algorithm improved`
     #[arg(short, long, default_value_t=false)]
     improved: bool,
 
+    /// The algorithm used to check the formula. `local` only decides the initial state (or `test_state`).
+    /// If it is not given, a formula without alternating fixed points (dependent alteration depth at most 1) is checked
+    /// in linear time, unless `--trace`, `--timeout` or `--max-iterations` is given, and other formulas with `naive`.
+    /// `--statistics` prints the algorithm that was used
+    #[arg(long, value_enum, verbatim_doc_comment)]
+    algorithm: Option<Algorithm>,
+
+    /// The number of threads the naive and improved algorithm may use, for conjunctions and disjunctions whose operands
+    /// do not share fixed point variables and for modalities over many states. The result does not depend on it.
+    #[arg(long, default_value_t=1, value_parser=clap::value_parser!(u16).range(1..), verbatim_doc_comment)]
+    threads: u16,
+
     /// Print intermediate output
     #[arg(short, long, default_value_t=false)]
     debug: bool,
@@ -41,6 +89,208 @@ struct Args {
     /// Test if state `test_state` is in the output
     #[arg(short, long, default_value_t=-1)]
     test_state: i64,
+
+    /// Minimise the LTS modulo branching bisimulation before checking the formula. This only keeps the result if the LTS has no
+    /// internal transitions or the formula has no modalities, otherwise a warning is printed
+    #[arg(short, long, default_value_t=false)]
+    reduce: bool,
+
+    /// The label of the internal action, used by hiding, parallel composition and the branching bisimulation reduction
+    #[arg(long, default_value_t=String::from("i"))]
+    internal_action: String,
+
+    /// Hide a label, i.e. rename it to the internal action. Can be given multiple times.
+    /// A label surrounded by slashes is a regex, e.g. `--hide '/free\(.*\)/'`
+    #[arg(long, verbatim_doc_comment)]
+    hide: Vec<String>,
+
+    /// Rename labels, given as `from=to`. Can be given multiple times, the first matching rule is applied.
+    /// `from` surrounded by slashes is a regex and `to` may refer to its groups, e.g. `--rename '/(lock)\(.*\)/=$1'`
+    /// Renaming is done after hiding.
+    #[arg(long, verbatim_doc_comment)]
+    rename: Vec<String>,
+
+    /// A label the components given by multiple `--aut-file`s synchronise on. Can be given multiple times.
+    /// Every component that has the label has to take part in it, all other labels are interleaved.
+    #[arg(long, verbatim_doc_comment)]
+    sync: Vec<String>,
+
+    /// If the initial state satisfies the formula, print a run from it that shows why, e.g. a path to a `plato` step.
+    /// With `--reduce`, the run is in the reduced LTS.
+    #[arg(short, long, default_value_t=false, verbatim_doc_comment)]
+    witness: bool,
+
+    /// If the initial state does not satisfy the formula, print a run from it that shows why, e.g. a path to a deadlock
+    /// or a cycle on which plato never eats. With `--reduce`, the run is in the reduced LTS.
+    #[arg(short, long, default_value_t=false, verbatim_doc_comment)]
+    counterexample: bool,
+
+    /// Also write the states and transitions of the counterexample to this .aut file, for visual inspection
+    #[arg(long, requires="counterexample")]
+    counterexample_aut: Option<std::path::PathBuf>,
+
+    /// If the initial state satisfies the formula, write the states and transitions that prove it to this .aut file.
+    /// The subformulas each state satisfies are written next to it, to a file with the extension .states
+    #[arg(long, verbatim_doc_comment)]
+    evidence: Option<std::path::PathBuf>,
+
+    /// Write the model checking parity game of the LTS and the formula to this .gm file, which the parity game solver can solve.
+    /// Vertex k is the k-th state and the formula, player 0 (even) wins it if and only if the state satisfies the formula
+    #[arg(long, verbatim_doc_comment)]
+    parity_game: Option<std::path::PathBuf>,
+
+    /// Write the boolean equation system of the LTS and the formula to this .bes file, in the textual format of mCRL2.
+    /// Equation k is the k-th state and the formula, the init variable is the initial state
+    #[arg(long, verbatim_doc_comment)]
+    bes: Option<std::path::PathBuf>,
+
+    /// Also check the formula with every algorithm and compare the results. If they disagree, print the smallest
+    /// subformula on which they do, with the result of every algorithm. Local and gauss are left out above 500 states
+    #[arg(long, default_value_t=false, verbatim_doc_comment)]
+    cross_check: bool,
+
+    /// Write a JSON line to this file for every iteration of a fixed point of the naive or improved algorithm, with the
+    /// variable, the variables of the fixed points around it (`path`), the number of the iteration and the size of the approximant
+    #[arg(long, verbatim_doc_comment)]
+    trace: Option<std::path::PathBuf>,
+
+    /// Also write the states of every approximant to the trace. With `--reduce`, these are states of the reduced LTS.
+    #[arg(long, default_value_t=false, requires="trace")]
+    trace_states: bool,
+
+    /// Stop the naive or improved algorithm after this many seconds. It then prints the last approximants of the fixed points
+    /// it was calculating, instead of the resulting set, and exits with status 3. Ctrl-C stops it in the same way.
+    #[arg(long, verbatim_doc_comment)]
+    timeout: Option<f64>,
+
+    /// Stop the naive or improved algorithm before it starts more than this many fixed point iterations in total, as for `--timeout`
+    #[arg(long)]
+    max_iterations: Option<i64>,
+}
+
+// The exit status when the naive or improved algorithm was stopped by `--timeout`, `--max-iterations` or Ctrl-C
+const EXIT_STOPPED: i32 = 3;
+
+/// definition of the subcommands
+// e.g.: `cargo run -- stats --aut-file ../input/part2-1/dining_2.aut`
+//       `cargo run -- simulate --aut-file ../input/part2-1/dining_2.aut --mcf-file ../input/part2-1/invariantly_possibly_eat.mcf`
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print facts about an LTS: its size, deadlocks, labels, strongly connected components and divergences
+    Stats {
+        /// The path to the .aut file
+        #[arg(short, long)]
+        aut_file: std::path::PathBuf,
+
+        /// The label of the internal action, used to find divergences
+        #[arg(long, default_value_t=String::from("i"))]
+        internal_action: String,
+    },
+
+    /// Walk through an LTS by hand, starting in its initial state
+    Simulate {
+        /// The path to the .aut file
+        #[arg(short, long)]
+        aut_file: std::path::PathBuf,
+
+        /// The path to an .mcf file, to show which of its subformulas hold in the current state
+        #[arg(short, long)]
+        mcf_file: Option<std::path::PathBuf>,
+
+        /// The seed used for random walks
+        #[arg(short, long, default_value_t=0)]
+        seed: u64,
+    },
+
+    /// Check whether an implementation LTS refines a specification LTS
+    Refines {
+        /// The path to the .aut file of the implementation
+        #[arg(short, long)]
+        implementation: std::path::PathBuf,
+
+        /// The path to the .aut file of the specification
+        #[arg(short, long)]
+        specification: std::path::PathBuf,
+
+        /// The refinement relation to check
+        #[arg(long, value_enum, default_value_t=Model::Traces)]
+        model: Model,
+
+        /// The label of the internal action, which is not observable
+        #[arg(long, default_value_t=String::from("i"))]
+        internal_action: String,
+    },
+
+    /// Generate an LTS of a parametric family and write it to an .aut file
+    Generate {
+        /// The family of models
+        #[arg(value_enum)]
+        family: Family,
+
+        /// The size of the model: the number of philosophers, children, processes or states
+        n: usize,
+
+        /// The path of the .aut file to write
+        #[arg(short, long)]
+        output: std::path::PathBuf,
+
+        /// The seed of a random LTS
+        #[arg(long, default_value_t=0)]
+        seed: u64,
+
+        /// The number of outgoing transitions of every state of a random LTS
+        #[arg(long, default_value_t=3)]
+        branching: usize,
+
+        /// The number of labels of a random LTS
+        #[arg(long, default_value_t=3)]
+        labels: usize,
+    },
+
+    /// Solve a boolean equation system, e.g. one written with `--bes`, and print the value of its init variable
+    SolveBes {
+        /// The path to the .bes file
+        #[arg(short, long)]
+        bes_file: std::path::PathBuf,
+
+        /// Only solve the equations the init variable depends on, instead of Gauss elimination of all equations
+        #[arg(short, long, default_value_t=false)]
+        local: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Algorithm {
+    /// evaluate every fixed point from scratch
+    Naive,
+    /// Emerson-Lei, only reset fixed points that depend on an alternating outer fixed point
+    Improved,
+    /// Liu-Smolka, explore only the (state, subformula) pairs needed for a single state
+    Local,
+    /// Gauss elimination of the boolean equation system of the formula and the LTS, meant for small LTSs
+    Gauss,
+    /// evaluate every fixed point from scratch on BDDs of the sets of states and of the transition relation
+    Symbolic,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Family {
+    /// dining philosophers, Plato eats with `plato`, the others with `others`
+    Dining,
+    /// demanding children, child 0 does `ask`, `wisdom` and `playing`
+    DemandingChildren,
+    /// a token ring protecting a critical section, with `request(k)`, `enter(k)` and `leave(k)`
+    MutexRing,
+    /// a random LTS with labels `a0`, `a1`, ...
+    Random,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Model {
+    /// trace inclusion
+    Traces,
+    /// stable failures refinement
+    Failures,
 }
 
 
@@ -48,9 +298,109 @@ fn main() {
     // Parse the arguments:
     let args: Args = Args::parse();
 
+    // Run the subcommand instead, if one is given:
+    if let Some(command) = args.command {
+        match command {
+            Command::Stats { aut_file, internal_action } => print_diagnostics(aut_file, &internal_action),
+            Command::Simulate { aut_file, mcf_file, seed } => {
+                let ltl: Ltl = read_aut_file(aut_file, false);
+                let mut simulator = Simulator::new(&ltl, seed);
+                if let Some(mcf_file) = mcf_file {
+                    simulator.load_formula(&read_mcf_file(mcf_file, false));
+                }
+                run_simulator(&mut simulator, std::io::stdin().lock(), &mut std::io::stdout())
+                    .expect("Should have been able to read commands and print the simulation");
+            }
+            Command::Refines { implementation, specification, model, internal_action } => {
+                let model = match model {
+                    Model::Traces => RefinementModel::Traces,
+                    Model::Failures => RefinementModel::Failures,
+                };
+                let implementation: Ltl = read_aut_file(implementation, false);
+                let specification: Ltl = read_aut_file(specification, false);
+                match check_refinement(&implementation, &specification, model, &internal_action) {
+                    None => println!("The implementation refines the specification ({:?})", model),
+                    Some(counterexample) => {
+                        println!("The implementation does not refine the specification ({:?})", model);
+                        println!("Shortest violating trace: {}", counterexample.trace.join(", "));
+                        match counterexample.violation {
+                            Violation::Trace => println!("The specification cannot do the last action"),
+                            Violation::Refusal(refusal) => println!("After this trace the implementation can refuse {{{}}}, the specification cannot",
+                                refusal.into_iter().collect::<Vec<String>>().join(", ")),
+                        }
+                    }
+                }
+            }
+            Command::Generate { family, n, output, seed, branching, labels } => {
+                let ltl: Ltl = match family {
+                    Family::Dining => dining_philosophers(n),
+                    Family::DemandingChildren => demanding_children(n),
+                    Family::MutexRing => mutex_ring(n),
+                    Family::Random => random_lts(n, branching, labels, seed),
+                };
+                write_aut_file(&ltl, &output);
+                println!("Wrote {} states and {} transitions to {:?}", ltl.nr_of_states,
+                    ltl.transitions.values().flat_map(|m| m.values()).map(|targets| targets.len()).sum::<usize>(), output);
+            }
+            Command::SolveBes { bes_file, local } => {
+                let contents: String = fs::read_to_string(&bes_file)
+                    .unwrap_or_else(|e| panic!("Should have been able to read the file {:?}: {}", bes_file, e));
+                let bes = Bes::parse(&contents);
+                let value = if local { solve_local(&bes, bes.init) } else { solve_gauss(&bes)[bes.init] };
+                println!("The init variable {} is: {}", bes.equations[bes.init].variable, value);
+            }
+        }
+        return;
+    }
+
     // Read the formula and ltl:
-    let f: Formula = read_mcf_file(args.mcf_file, args.debug);
-    let ltl: Ltl = read_aut_file(args.aut_file, args.debug);
+    let f: Formula = read_mcf_file(args.mcf_file.expect("--mcf-file is required"), args.debug);
+    let components: Vec<Ltl> = args.aut_file.into_iter().map(|file| read_aut_file(file, args.debug)).collect();
+
+    // If multiple ltls are given, build their parallel composition, every component synchronises on the given labels it has:
+    let mut ltl: Ltl = if components.len() == 1 {
+        components.into_iter().next().unwrap()
+    } else {
+        let sync: Vec<HashSet<String>> = components.iter()
+            .map(|component| component.get_labels().into_iter().filter(|label| args.sync.contains(label)).collect())
+            .collect();
+        let product = compose(&components, &sync, &args.internal_action);
+        if args.statistics {
+            println!("The parallel composition of {} components has {} states", components.len(), product.nr_of_states);
+        }
+        product
+    };
+
+    // Hide and rename actions, so the formula can use the labels it expects:
+    if !args.hide.is_empty() {
+        let patterns: Vec<LabelPattern> = args.hide.iter().map(|pattern| LabelPattern::new(pattern)).collect();
+        ltl = ltl.hide(&patterns, &args.internal_action);
+    }
+    if !args.rename.is_empty() {
+        let renaming: Vec<(LabelPattern, String)> = args.rename.iter().map(|rule| {
+            let (from, to) = rule.rsplit_once('=')
+                .unwrap_or_else(|| panic!("Renaming '{}' should be of the form `from=to`", rule));
+            (LabelPattern::new(from), to.to_string())
+        }).collect();
+        ltl = ltl.rename(&renaming);
+    }
+
+    let first_state: i64 = ltl.first_state;
+
+    // If requested, minimise the ltl modulo branching bisimulation, remembering to which reduced state each state is mapped:
+    let mut state_map: Option<Vec<i64>> = None;
+    if args.reduce {
+        // Modalities can distinguish branching bisimilar states if there are internal steps, so the verdict may change
+        if !preserves_formula(&ltl, &f.root_node, &args.internal_action) {
+            println!("Warning: the LTS has internal `{}` transitions and the formula has modalities, its result is not invariant under branching bisimulation reduction", args.internal_action);
+        }
+        let (reduced, reduced_state_map) = branching_bisimulation_reduction(&ltl, &args.internal_action);
+        if args.statistics {
+            println!("Reduced the LTS modulo branching bisimulation from {} to {} states", ltl.nr_of_states, reduced.nr_of_states);
+        }
+        ltl = reduced;
+        state_map = Some(reduced_state_map);
+    }
 
     // If we want the nesting depth, alteration depth and dependent alteration depth, calculate these and print them:
     if args.statistics {
@@ -60,13 +410,149 @@ fn main() {
         print!("The dependent alteration depth for this formula is: {}\n", dependent_alteration_depth);
     }
 
-    // Execute the required algorithm and print the result:
-    if args.improved {
-        let (result_set, iterations) = execute_improved(f, ltl);
-        print_set(result_set, iterations, args.test_state, args.statistics);
+    if let Some(file_path) = &args.parity_game {
+        write_parity_game(&f, &ltl, file_path);
+    }
+    if let Some(file_path) = &args.bes {
+        let bes = to_bes(&f, &ltl);
+        let mut contents: Vec<u8> = Vec::new();
+        bes.write(&mut contents).expect("Should have been able to write the equations to memory");
+        fs::write(file_path, contents)
+            .unwrap_or_else(|e| panic!("Should have been able to write {:?}: {}", file_path, e));
+        println!("Wrote the boolean equation system ({} equations) to {:?}", bes.equations.len(), file_path);
+    }
+
+    if args.cross_check {
+        match cross_check(&f, &ltl) {
+            Ok(result) => println!("Cross-check: all algorithms agree, {} states satisfy the formula", result.len()),
+            Err(disagreement) => {
+                println!("Cross-check: the algorithms disagree on the subformula {}", disagreement.subformula);
+                for (algorithm, result) in disagreement.results.iter() {
+                    println!("  {: <10} {}", algorithm, format_states(&result.iter().cloned().collect::<Vec<i64>>()));
+                }
+                println!("  They disagree on the states {}", format_states(&disagreement.states.into_iter().collect::<Vec<i64>>()));
+            }
+        }
+    }
+
+    // Find the witness before the formula is handed to the solver:
+    let witness: Option<Option<Witness>> = args.witness.then(|| find_witness(&f, &ltl));
+    let counterexample: Option<Option<Witness>> = args.counterexample.then(|| find_counterexample(&f, &ltl));
+    let evidence: Option<Option<Evidence>> = args.evidence.as_ref().map(|_| find_evidence(&f, &ltl));
+
+    // Execute the required algorithm:
+    let chosen: Option<Algorithm> = if args.improved { Some(Algorithm::Improved) } else { args.algorithm };
+    let algorithm: Algorithm = chosen.unwrap_or(Algorithm::Naive);
+    if algorithm == Algorithm::Local {
+        // Only decide the state we are interested in, or the state it is mapped to if we reduced the ltl:
+        let state: i64 = if args.test_state != -1 { args.test_state } else { first_state };
+        let checked_state: i64 = state_map.as_ref().map_or(state, |state_map| state_map[state as usize]);
+        let (holds, explored) = execute_local(f, &ltl, checked_state);
+        println!("The state {} is in the resulting set: {}", state, holds);
+        if args.statistics {
+            println!("Total number of explored (state, subformula) pairs: {}", explored);
+        }
     } else {
-        let (result_set, iterations) = execute(f, ltl);
-        print_set(result_set, iterations, args.test_state, args.statistics);
+        // Every algorithm has its own statistics, which are printed after the result with the algorithm that was used.
+        // If no algorithm was chosen, a formula without alternating fixed points is checked in linear time, unless the fixed point iterations are needed:
+        let fixed_point_options = args.trace.is_some() || args.timeout.is_some() || args.max_iterations.is_some();
+        let fast_path = chosen.is_none() && !fixed_point_options;
+        let alternation_free = if fast_path { execute_alternation_free(&f, &ltl) } else { None };
+        let (mut result_set, used, statistics): (HashSet<i64>, &str, Vec<(&str, i64)>) = match (algorithm, alternation_free) {
+            (Algorithm::Gauss, _) => {
+                let (result_set, equations) = execute_gauss(f, &ltl);
+                (result_set, "gauss", vec![("Total number of equations", equations)])
+            }
+            (Algorithm::Symbolic, _) => {
+                let (result_set, iterations, nodes) = execute_symbolic(f, &ltl);
+                (result_set, "symbolic", vec![("Total number of fixpoint iterations", iterations), ("Total number of BDD nodes", nodes)])
+            }
+            (_, Some((result_set, updates))) => (result_set, "alternation-free", vec![("Total number of state updates", updates)]),
+            _ => {
+                let trace = match (&args.trace, args.trace_states) {
+                    (None, _) => Trace::Off,
+                    (Some(_), false) => Trace::Sizes,
+                    (Some(_), true) => Trace::States,
+                };
+                let options = Options {
+                    threads: args.threads as usize,
+                    trace,
+                    timeout: args.timeout.map(Duration::from_secs_f64),
+                    max_iterations: args.max_iterations,
+                    ..Options::default()
+                };
+                // The first Ctrl-C stops the algorithm, the second one the program:
+                let cancellation = options.cancellation.clone();
+                ctrlc::set_handler(move || {
+                    if cancellation.is_cancelled() {
+                        std::process::exit(EXIT_STOPPED);
+                    }
+                    cancellation.cancel();
+                }).expect("Should have been able to handle Ctrl-C");
+                let (result_set, statistics) = if algorithm == Algorithm::Improved {
+                    execute_improved_with_options(f, &ltl, &options)
+                } else {
+                    execute_with_options(f, &ltl, &options)
+                };
+                if let Some(file_path) = &args.trace {
+                    write_trace(&statistics.trace, file_path);
+                }
+                let mut lines = vec![
+                    ("Total number of fixpoint iterations", statistics.iterations),
+                    ("Total number of cache hits", statistics.cache_hits),
+                ];
+                if algorithm == Algorithm::Improved {
+                    lines.push(("Total number of state updates", statistics.state_updates));
+                }
+                let used = if algorithm == Algorithm::Improved { "improved" } else { "naive" };
+                if let Some(stop) = statistics.stopped {
+                    print_stopped(stop, args.timeout, args.max_iterations, &statistics.approximants, state_map.as_deref());
+                    if args.statistics {
+                        println!("Algorithm: {}", used);
+                        for (name, value) in lines {
+                            println!("{}: {}", name, value);
+                        }
+                    }
+                    std::process::exit(EXIT_STOPPED);
+                }
+                (result_set, used, lines)
+            }
+        };
+
+        // If we checked a reduced ltl, translate the result back to the states of the original ltl:
+        if let Some(state_map) = state_map {
+            result_set = to_original_states(&result_set, &state_map);
+        }
+        print_set(result_set, args.test_state);
+        if args.statistics {
+            println!("Algorithm: {}", used);
+            for (name, value) in statistics {
+                println!("{}: {}", name, value);
+            }
+        }
+    }
+
+    match witness {
+        Some(Some(witness)) => print_witness("Witness", &witness),
+        Some(None) => println!("No witness, the initial state {} does not satisfy the formula", ltl.first_state),
+        None => {}
+    }
+    match counterexample {
+        Some(Some(counterexample)) => {
+            print_witness("Counterexample", &counterexample);
+            if let Some(file_path) = args.counterexample_aut {
+                let (sub_ltl, original) = counterexample.to_ltl(ltl.first_state);
+                write_aut_file(&sub_ltl, &file_path);
+                println!("Wrote the counterexample to {:?}, its states are the states {} of the LTS", file_path, format_states(&original));
+            }
+        }
+        Some(None) => println!("No counterexample, the initial state {} satisfies the formula", ltl.first_state),
+        None => {}
+    }
+    match (evidence, args.evidence) {
+        (Some(Some(evidence)), Some(file_path)) => write_evidence(&evidence, ltl.first_state, &file_path),
+        (Some(None), _) => println!("No evidence, the initial state {} does not satisfy the formula", ltl.first_state),
+        _ => {}
     }
 
     println!("\nTerminated Succesfully");
@@ -74,9 +560,9 @@ fn main() {
 }
 
 /**
- * Method to print the resulting set, and optionally the number of iterations and whether a given state is in the set
+ * Method to print the resulting set, and optionally whether a given state is in the set
  */
-fn print_set(set: HashSet<i64>, iterations: i64, test_state: i64, statistics:bool) {
+fn print_set(set: HashSet<i64>, test_state: i64) {
     print!("Resulting set: ");
     print!("{{");
     for (i, el) in set.iter().enumerate()  {
@@ -90,73 +576,181 @@ fn print_set(set: HashSet<i64>, iterations: i64, test_state: i64, statistics:boo
     if test_state != -1 {
         println!("The state {} is in the resulting set: {}", test_state, set.contains(&test_state));
     }
-    // If we want to print the number of iterations, print this:
-    if statistics {
-        println!("Total number of fixpoint iterations: {}", iterations);
-    }
 }
 
+/**
+ * Get the states of the original ltl that are mapped to the given states of the reduced ltl
+ */
+fn to_original_states(states: &HashSet<i64>, state_map: &[i64]) -> HashSet<i64> {
+    (0..state_map.len() as i64)
+        .filter(|state| states.contains(&state_map[*state as usize]))
+        .collect()
+}
 
 /**
- * Read .aut file and convert to DataType
+ * Method to print why the algorithm stopped, and the last approximants of the fixed points it was calculating from the outside in.
+ *   An approximant of a least fixed point is a subset of it, and one of a greatest fixed point a superset, for the values of the
+ *   variables around it. So those of the outermost fixed points approximate them, and the formula if it is one of them.
  */
-fn read_aut_file(file_path: std::path::PathBuf, debug: bool) -> Ltl {
-    if !file_path.exists() {
-        panic!("File {:?} does not exist", file_path);
+fn print_stopped(stop: Stop, timeout: Option<f64>, max_iterations: Option<i64>, approximants: &[Approximant], state_map: Option<&[i64]>) {
+    match stop {
+        Stop::Timeout => println!("Stopped: the timeout of {} seconds ran out", timeout.unwrap_or_default()),
+        Stop::MaxIterations => println!("Stopped: the maximum of {} fixpoint iterations was reached", max_iterations.unwrap_or_default()),
+        Stop::Cancelled => println!("Stopped: cancelled"),
     }
-
-    if "aut" != file_path.extension().unwrap() {
-        panic!("File {:?} should have been of type .aut", file_path);
+    let mut approximants: Vec<&Approximant> = approximants.iter().collect();
+    approximants.sort_by_key(|approximant| approximant.path.len());
+    println!("Last approximants of the fixed points that were being calculated:");
+    for approximant in approximants {
+        let mut states: Vec<i64> = match state_map {
+            Some(state_map) => to_original_states(&approximant.states, state_map).into_iter().collect(),
+            None => approximant.states.iter().cloned().collect(),
+        };
+        states.sort();
+        let (binder, approximation) = match approximant.op {
+            types::formula::Operator::LeastFixpoint => ("mu", "an under-approximation"),
+            _ => ("nu", "an over-approximation"),
+        };
+        println!("  {} {} (path {}) after {} iterations: {} states, {} {}", binder, approximant.variable, approximant.path.join("."),
+            approximant.iteration, states.len(), approximation, format_states(&states));
     }
+}
 
-    let contents: String = fs::read_to_string(file_path)
-        .expect("Should have been able to read the file");
+/**
+ * Method to print a witness or counterexample as its steps, followed by the steps that repeat forever if it is a lasso
+ */
+fn print_witness(name: &str, witness: &Witness) {
+    let print_steps = |steps: &[Step]| {
+        for (source, label, target) in steps {
+            println!("    {} -{}-> {}", source, label, target);
+        }
+    };
+    println!("{} ({} steps):", name, witness.prefix.len());
+    print_steps(&witness.prefix);
+    if !witness.cycle.is_empty() {
+        println!("  then repeat forever ({} steps):", witness.cycle.len());
+        print_steps(&witness.cycle);
+    }
+}
 
-    let mut lines = contents.lines();
+/**
+ * Method to write the evidence to the .aut file `file_path`, and the subformulas every state satisfies to a .states file next to it
+ */
+fn write_evidence(evidence: &Evidence, first_state: i64, file_path: &std::path::Path) {
+    let (sub_ltl, original) = evidence.to_ltl(first_state);
+    write_aut_file(&sub_ltl, file_path);
+
+    let annotations_path = file_path.with_extension("states");
+    let mut annotations = String::from("% state (state in the checked LTS): subformulas it satisfies\n");
+    for (state, original_state) in original.iter().enumerate() {
+        let subformulas: Vec<String> = evidence.states[original_state].iter().cloned().collect();
+        annotations.push_str(&format!("{} ({}): {}\n", state, original_state, subformulas.join("; ")));
+    }
+    fs::write(&annotations_path, annotations)
+        .unwrap_or_else(|e| panic!("Should have been able to write {:?}: {}", annotations_path, e));
 
-    // Initialize header, e.g. "des (123,456,789)     "
-    let first_line = lines.nth(0)
-        .expect("File cannot be empty");
+    println!("Wrote the evidence ({} states, {} transitions) to {:?} and {:?}", sub_ltl.nr_of_states,
+        evidence.transitions.len(), file_path, annotations_path);
+}
 
-    let (des, last) = first_line.split_at(5);
-    if "des (" != des {
-        panic!(".aut file should contain `des (...)` as first line, file started with {}", des);
+/**
+ * Method to write the parity game of f and the ltl to `file_path`, and print the subformulas the vertices are named after
+ */
+fn write_parity_game(f: &Formula, ltl: &Ltl, file_path: &std::path::Path) {
+    let game = to_parity_game(f, ltl);
+    let mut contents: Vec<u8> = Vec::new();
+    game.write(&mut contents).expect("Should have been able to write the game to memory");
+    fs::write(file_path, contents)
+        .unwrap_or_else(|e| panic!("Should have been able to write {:?}: {}", file_path, e));
+
+    println!("Wrote the parity game ({} vertices) to {:?}, vertex `[g] |= s` is state s and subformula g of:", game.vertices.len(), file_path);
+    for (index, subformula) in game.subformulas.iter().enumerate() {
+        println!("  {}: {}", index, subformula);
     }
+}
 
-    let seconds: Vec<&str> = last.split(")").collect();
-    let nums: Vec<&str> = seconds[0].split(",").collect();
-
-    let mut ltl: Ltl = Ltl::new(
-        to_int64(nums[0]),
-        to_int64(nums[1]), 
-        to_int64(nums[2])
-    );
+/**
+ * Write the trace of the fixed point iterations to `file_path`, as one JSON object per line
+ */
+fn write_trace(trace: &[TraceRecord], file_path: &std::path::Path) {
+    let contents: String = trace.iter().map(|record| record.to_json() + "\n").collect();
+    fs::write(file_path, contents)
+        .unwrap_or_else(|e| panic!("Should have been able to write {:?}: {}", file_path, e));
+}
 
-    // initialize transitions
 
-    for part in lines.skip(1) {
-        let (start, last) = part.split_at(1);
-        if "(" != start {
-            panic!("Line '{}' did not start with '('", part)
+/**
+ * Method to print the diagnostics of the ltl in `file_path`, including mismatches between the header and body of the file
+ */
+fn print_diagnostics(file_path: std::path::PathBuf, internal_action: &str) {
+    let (header, transitions) = parse_aut_file(file_path);
+    let mismatches = check_aut_header(header, &transitions);
+
+    // Build the ltl from the transitions that fit the header, mismatches are reported below
+    let (first_state, nr_of_transitions, nr_of_states) = header;
+    let mut ltl: Ltl = Ltl::new(first_state, nr_of_transitions, nr_of_states);
+    for (start_state, label, end_state) in transitions {
+        if (0..nr_of_states).contains(&start_state) && (0..nr_of_states).contains(&end_state) {
+            ltl.add_transition(start_state, &label, end_state, false);
         }
-        let seconds: Vec<&str> = last.split(")").collect();
-        let nums: Vec<&str> = seconds[0].split(",").collect();
-        let label: &str = nums[1].split("\"").nth(1)
-            .expect("No label found in {part}");
-
-        ltl.add_transition(
-            to_int64(nums[0]), 
-            label, 
-            to_int64(nums[2]),
-            debug
-        );
     }
+    let diagnostics = diagnose(&ltl, internal_action);
+
+    println!("Initial state:         {}", first_state);
+    println!("Number of states:      {}", diagnostics.nr_of_states);
+    println!("Number of transitions: {}", diagnostics.nr_of_transitions);
+    println!("Maximum out-degree:    {} (state {})", diagnostics.max_out_degree, diagnostics.max_out_degree_state);
+    println!("Deadlock states:       {} {}", diagnostics.deadlock_states.len(), format_states(&diagnostics.deadlock_states));
+    println!("Divergent states:      {} {}", diagnostics.divergent_states.len(), format_states(&diagnostics.divergent_states));
+    println!("Strongly connected components: {} (largest has {} states)", diagnostics.nr_of_sccs, diagnostics.largest_scc);
+    println!("Bottom components:     {}", diagnostics.bottom_sccs.len());
+    for component in diagnostics.bottom_sccs.iter().take(10) {
+        println!("    {} states {}", component.len(), format_states(component));
+    }
+    if diagnostics.bottom_sccs.len() > 10 {
+        println!("    ...");
+    }
+    println!("Labels:                {}", diagnostics.label_histogram.len());
+    for (label, count) in &diagnostics.label_histogram {
+        println!("    {: <20} {}", label, count);
+    }
+    if mismatches.is_empty() {
+        println!("The header matches the body");
+    } else {
+        println!("The header does not match the body:");
+        for mismatch in mismatches {
+            println!("    {}", mismatch);
+        }
+    }
+}
 
-    return ltl;
+/**
+ * Format (at most the first 10 of) a list of states as `{0,1,2,...}`
+ */
+fn format_states(states: &[i64]) -> String {
+    let mut shown: Vec<String> = states.iter().take(10).map(|state| state.to_string()).collect();
+    if states.len() > 10 {
+        shown.push(String::from("..."));
+    }
+    format!("{{{}}}", shown.join(","))
 }
 
-fn to_int64(f: &str) -> i64 {
-    return f.parse::<i64>().unwrap()
+/**
+ * Write ltl to an .aut file, the transitions are sorted on start state, label and end state
+ */
+fn write_aut_file(ltl: &Ltl, file_path: &std::path::Path) {
+    let mut transitions: Vec<(i64, &String, i64)> = ltl.transitions.iter()
+        .flat_map(|(state, state_map)| state_map.iter()
+            .flat_map(move |(label, targets)| targets.iter().map(move |target| (*state, label, *target))))
+        .collect();
+    transitions.sort();
+
+    let mut contents = format!("des ({},{},{})\n", ltl.first_state, transitions.len(), ltl.nr_of_states);
+    for (start_state, label, end_state) in transitions {
+        contents.push_str(&format!("({},\"{}\",{})\n", start_state, label, end_state));
+    }
+    fs::write(file_path, contents)
+        .unwrap_or_else(|e| panic!("Should have been able to write {:?}: {}", file_path, e));
 }
 
 /**
@@ -0,0 +1,173 @@
+use crate::types::formula::{Formula, Node, Operator, formula_to_string};
+use crate::types::transition_system::TransitionSystem;
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::io::Write;
+
+
+// A subformula of the formula the game is built for, with its variables replaced by (the index of) their binder
+enum Subformula {
+    True,
+    False,
+    And(usize, usize),
+    Or(usize, usize),
+    Diamond(String, usize),
+    Box(String, usize),
+    Fixpoint(usize),
+}
+
+// Specify custom type `ParityGame`
+// the model checking game of an LTS and a formula, in the form of the .gm files of PGSolver that the parity game solver reads:
+// player 0 (even) wants to show that a state satisfies a subformula, player 1 (odd) that it does not, and the player
+// that owns a vertex chooses its successor. The player of the least priority that occurs infinitely often wins.
+
+pub struct ParityGame {
+    pub vertices: Vec<Vertex>,
+    pub subformulas: Vec<String>,
+}
+
+pub struct Vertex {
+    pub priority: i64,
+    pub owner: i64,
+    pub successors: Vec<usize>,
+    pub name: String,
+}
+
+/**
+ * Build the model checking game of a Formula f and an LTL instance.
+ *
+ * The vertices are the pairs (state, subformula) that can be reached from the pairs (state, f), where vertex k is the pair
+ * (k-th state, f), so player 0 wins vertex k if and only if the k-th state satisfies f. A vertex is named `"[g] |= s"`, where
+ * g is the subformula and s the state, like the names of the games in `parity-game-solver/input`, e.g. `"[X.]  |= 0"`.
+ *
+ * Player 0 owns the disjunctions and diamonds, player 1 the conjunctions and boxes. A fixed point gets the least priority
+ * of its parity (even for nu, odd for mu) that is not smaller than the priority of the fixed point around it, and the other
+ * subformulas get the priority of the fixed point around them. A pair that has no successors gets a self loop that is won
+ * by the other player, e.g. (s, false) or (s, <a>g) where s has no a-transitions.
+ */
+pub fn to_parity_game<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T) -> ParityGame {
+    let mut subformulas: Vec<(Subformula, i64, String)> = Vec::new();
+    let root = number_subformulas(&f.root_node, &HashMap::new(), 0, &mut subformulas);
+
+    let mut states: Vec<i64> = instance.states();
+    states.sort();
+    let mut identifiers: HashMap<(i64, usize), usize> = HashMap::new();
+    let mut queue: VecDeque<(i64, usize)> = VecDeque::new();
+    let mut identify = |pair: (i64, usize), queue: &mut VecDeque<(i64, usize)>| {
+        let next = identifiers.len();
+        *identifiers.entry(pair).or_insert_with(|| {
+            queue.push_back(pair);
+            next
+        })
+    };
+    for state in states {
+        identify((state, root), &mut queue);
+    }
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    while let Some((state, index)) = queue.pop_front() {
+        let (subformula, priority, text) = &subformulas[index];
+        let (owner, pairs): (i64, Vec<(i64, usize)>) = match subformula {
+            Subformula::True => (1, vec![]),
+            Subformula::False => (0, vec![]),
+            Subformula::And(lhs, rhs) => (1, vec![(state, *lhs), (state, *rhs)]),
+            Subformula::Or(lhs, rhs) => (0, vec![(state, *lhs), (state, *rhs)]),
+            Subformula::Diamond(label, body) => (0, successor_pairs(instance, state, label, *body)),
+            Subformula::Box(label, body) => (1, successor_pairs(instance, state, label, *body)),
+            Subformula::Fixpoint(body) => (0, vec![(state, *body)]),
+        };
+        let identifier = vertices.len();
+        let (priority, successors) = if pairs.is_empty() {
+            // The owner cannot move, so it loses
+            (1 - owner, vec![identifier])
+        } else {
+            let mut successors: Vec<usize> = pairs.into_iter().map(|pair| identify(pair, &mut queue)).collect();
+            successors.dedup();
+            (*priority, successors)
+        };
+        vertices.push(Vertex { priority, owner, successors, name: format!("[{}] |= {}", text, state) });
+    }
+
+    ParityGame {
+        vertices,
+        subformulas: subformulas.into_iter().map(|(_, _, text)| text).collect(),
+    }
+}
+
+impl ParityGame {
+    /**
+     * Write the game in the .gm format, e.g.
+     *   parity 22;
+     *   0 0 0 3 "[nu X. ([a]X && <b>true)] |= 0";
+     */
+    pub fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "parity {};", self.vertices.len() as i64 - 1)?;
+        for (identifier, vertex) in self.vertices.iter().enumerate() {
+            let successors: Vec<String> = vertex.successors.iter().map(|successor| successor.to_string()).collect();
+            writeln!(writer, "{} {} {} {} \"{}\";", identifier, vertex.priority, vertex.owner, successors.join(","), vertex.name)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Number node and its subformulas in preorder, where scope maps the variables in scope to the index of their binder and
+ *   priority is the priority of the fixed point around node. Variables are not numbered, they are their binder.
+ *   Returns the index of node.
+ */
+fn number_subformulas(node: &Node, scope: &HashMap<String, usize>, priority: i64, subformulas: &mut Vec<(Subformula, i64, String)>) -> usize {
+    // Like the other solvers, a variable that is not bound is the empty set
+    if let Node::Variable(variable) = node {
+        if let Some(binder) = scope.get(variable) {
+            return *binder;
+        }
+    }
+    let priority = match node {
+        Node::FixPointExpr { op, .. } => {
+            let parity = if *op == Operator::LeastFixpoint { 1 } else { 0 };
+            if priority % 2 == parity { priority } else { priority + 1 }
+        }
+        _ => priority,
+    };
+
+    // The subformulas are filled in after the node itself is numbered
+    let index = subformulas.len();
+    subformulas.push((Subformula::False, priority, formula_to_string(node)));
+    let subformula = match node {
+        Node::UnaryExpr { op: Operator::SimpleTrue } => Subformula::True,
+        Node::BinaryExpr { op, lhs, rhs } => {
+            match (op, &**lhs) {
+                (Operator::DiamondModality, Node::Action(label)) => Subformula::Diamond(label.clone(), number_subformulas(rhs, scope, priority, subformulas)),
+                (Operator::BoxModality, Node::Action(label)) => Subformula::Box(label.clone(), number_subformulas(rhs, scope, priority, subformulas)),
+                (Operator::Conjunction, _) => {
+                    let lhs = number_subformulas(lhs, scope, priority, subformulas);
+                    Subformula::And(lhs, number_subformulas(rhs, scope, priority, subformulas))
+                }
+                (Operator::Disjunction, _) => {
+                    let lhs = number_subformulas(lhs, scope, priority, subformulas);
+                    Subformula::Or(lhs, number_subformulas(rhs, scope, priority, subformulas))
+                }
+                _ => unreachable!("A modality should have an action on its left hand side"),
+            }
+        }
+        Node::FixPointExpr { op: _, variable, rhs, surrounding_binder: _ } => {
+            let mut scope = scope.clone();
+            scope.insert(variable.clone(), index);
+            Subformula::Fixpoint(number_subformulas(rhs, &scope, priority, subformulas))
+        }
+        _ => Subformula::False,
+    };
+    subformulas[index].0 = subformula;
+    index
+}
+
+/**
+ * Get the pairs (target, body) for all label-transitions from state, in order of the target
+ */
+fn successor_pairs<T: TransitionSystem + ?Sized>(instance: &T, state: i64, label: &str, body: usize) -> Vec<(i64, usize)> {
+    let mut targets: Vec<i64> = instance.successors(state, label);
+    targets.sort();
+    targets.dedup();
+    targets.into_iter().map(|target| (target, body)).collect()
+}
@@ -0,0 +1,170 @@
+use crate::types::ltl::Ltl;
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RefinementModel {
+    Traces,   // every trace of the implementation is a trace of the specification
+    Failures, // stable failures: additionally, every refusal of a stable implementation state is allowed by the specification
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Violation {
+    Trace,                   // the specification cannot do the last action of the trace
+    Refusal(BTreeSet<String>), // after the trace the implementation can stably refuse these actions, the specification cannot
+}
+
+// A shortest trace of visible actions after which the implementation does something the specification does not allow
+#[derive(Debug, PartialEq)]
+pub struct Counterexample {
+    pub trace: Vec<String>,
+    pub violation: Violation,
+}
+
+/**
+ * Check whether `implementation` refines `specification` in the given model, where `internal_action` is not observable.
+ * Returns None if it does, otherwise a counterexample with a shortest violating trace.
+ *
+ * The specification is determinised on the fly: we search the pairs (implementation state, set of specification states
+ * reachable by the same trace), breadth first in the number of visible actions, where a violation of a trace is only returned
+ * once no refusal with a shorter trace can be found anymore. A pair is skipped if we already visited
+ * a pair with the same implementation state and a subset of its specification states (antichain), as fewer specification
+ * states allow less behaviour, so any violation from the skipped pair is found from the visited one too.
+ */
+pub fn check_refinement(implementation: &Ltl, specification: &Ltl, model: RefinementModel, internal_action: &str) -> Option<Counterexample> {
+    let initial = internal_closure(specification, BTreeSet::from([specification.first_state]), internal_action);
+
+    // All pairs found so far, with the pair and visible action they were found from, so we can rebuild the trace
+    let mut pairs: Vec<(i64, BTreeSet<i64>)> = vec![(implementation.first_state, initial)];
+    let mut parent: Vec<Option<(usize, Option<String>)>> = vec![None];
+    // The number of visible actions in the trace of each pair
+    let mut depth: Vec<usize> = vec![0];
+    let mut visited: HashMap<i64, Vec<BTreeSet<i64>>> = HashMap::new();
+    // Internal steps do not extend the trace, so they go to the front of the queue
+    let mut queue: VecDeque<usize> = VecDeque::from([0]);
+    // A trace violation is one action longer than the trace of the pair it is found from, so a refusal of a later pair
+    // with the same trace length is shorter. We keep the first one until all pairs of that length are checked.
+    let mut trace_violation: Option<Counterexample> = None;
+
+    while let Some(index) = queue.pop_front() {
+        if trace_violation.as_ref().is_some_and(|counterexample| depth[index] >= counterexample.trace.len()) {
+            break;
+        }
+        let (state, spec_states) = pairs[index].clone();
+        let antichain = visited.entry(state).or_default();
+        if antichain.iter().any(|seen| seen.is_subset(&spec_states)) {
+            continue;
+        }
+        antichain.retain(|seen| !spec_states.is_subset(seen));
+        antichain.push(spec_states.clone());
+
+        let state_map = implementation.transitions.get(&state);
+        let stable = state_map.and_then(|m| m.get(internal_action)).is_none_or(|targets| targets.is_empty());
+
+        // In a stable state the implementation refuses everything it cannot do, some stable specification state has to as well
+        if model == RefinementModel::Failures && stable {
+            let initials: HashSet<&String> = state_map.map(|m| m.keys().collect()).unwrap_or_default();
+            let allowed = spec_states.iter().any(|spec_state| {
+                let spec_map = specification.transitions.get(spec_state);
+                let spec_stable = spec_map.and_then(|m| m.get(internal_action)).is_none_or(|targets| targets.is_empty());
+                spec_stable && spec_map.is_none_or(|m| m.keys().all(|label| initials.contains(label)))
+            });
+            if !allowed {
+                let refusal: BTreeSet<String> = specification.get_labels().union(&implementation.get_labels())
+                    .filter(|label| *label != internal_action && !initials.contains(label))
+                    .cloned()
+                    .collect();
+                return Some(Counterexample { trace: rebuild_trace(&parent, index), violation: Violation::Refusal(refusal) });
+            }
+        }
+
+        let mut labels: Vec<&String> = state_map.map(|m| m.keys().collect()).unwrap_or_default();
+        labels.sort();
+        for label in labels {
+            let targets = &state_map.unwrap()[label];
+            let mut sorted_targets: Vec<&i64> = targets.iter().collect();
+            sorted_targets.sort();
+            if label == internal_action {
+                for target in sorted_targets {
+                    if is_dominated(&visited, *target, &spec_states) {
+                        continue;
+                    }
+                    pairs.push((*target, spec_states.clone()));
+                    parent.push(Some((index, None)));
+                    depth.push(depth[index]);
+                    queue.push_front(pairs.len() - 1);
+                }
+                continue;
+            }
+
+            // Determinise: the specification states reachable with this action
+            let next: BTreeSet<i64> = spec_states.iter()
+                .filter_map(|spec_state| specification.transitions.get(spec_state).and_then(|m| m.get(label)))
+                .flatten()
+                .cloned()
+                .collect();
+            if next.is_empty() {
+                if trace_violation.is_none() {
+                    let mut trace = rebuild_trace(&parent, index);
+                    trace.push(label.clone());
+                    trace_violation = Some(Counterexample { trace, violation: Violation::Trace });
+                }
+                continue;
+            }
+            let next = internal_closure(specification, next, internal_action);
+            for target in sorted_targets {
+                if is_dominated(&visited, *target, &next) {
+                    continue;
+                }
+                pairs.push((*target, next.clone()));
+                parent.push(Some((index, Some(label.clone()))));
+                depth.push(depth[index] + 1);
+                queue.push_back(pairs.len() - 1);
+            }
+        }
+    }
+
+    trace_violation
+}
+
+/**
+ * Check whether a pair with implementation state `state` and a subset of `spec_states` was already visited
+ */
+fn is_dominated(visited: &HashMap<i64, Vec<BTreeSet<i64>>>, state: i64, spec_states: &BTreeSet<i64>) -> bool {
+    visited.get(&state).is_some_and(|antichain| antichain.iter().any(|seen| seen.is_subset(spec_states)))
+}
+
+/**
+ * Extend a set of states with all states reachable from it using internal transitions
+ */
+fn internal_closure(instance: &Ltl, states: BTreeSet<i64>, internal_action: &str) -> BTreeSet<i64> {
+    let mut closure = states.clone();
+    let mut todo: Vec<i64> = states.into_iter().collect();
+    while let Some(state) = todo.pop() {
+        for target in instance.transitions.get(&state).and_then(|m| m.get(internal_action)).into_iter().flatten() {
+            if closure.insert(*target) {
+                todo.push(*target);
+            }
+        }
+    }
+    closure
+}
+
+/**
+ * Follow the parents of pair `index` back to the initial pair and collect the visible actions on the way
+ */
+fn rebuild_trace(parent: &[Option<(usize, Option<String>)>], mut index: usize) -> Vec<String> {
+    let mut trace: Vec<String> = Vec::new();
+    while let Some((previous, label)) = &parent[index] {
+        if let Some(label) = label {
+            trace.push(label.clone());
+        }
+        index = *previous;
+    }
+    trace.reverse();
+    trace
+}
@@ -0,0 +1,257 @@
+use crate::types::transition_system::TransitionSystem;
+use crate::types::formula::{Formula, Node, formula_to_string, find_free_variables};
+use crate::solver::execute;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, Write};
+
+
+// Specify custom type `Simulator`
+// walks a transition system (e.g. an `Ltl`) by hand, starting in its initial state
+
+pub struct Simulator<'a, T: TransitionSystem + ?Sized> {
+    pub instance: &'a T,
+    pub trace: Vec<(i64, String, i64)>, // the transitions taken so far
+    pub subformulas: Vec<(String, HashSet<i64>)>, // closed subformulas of the loaded formula, with the states satisfying them
+    labels: Vec<String>, // the labels of instance, sorted
+    rng: StdRng,
+}
+
+impl<'a, T: TransitionSystem + ?Sized> Simulator<'a, T> {
+    /**
+     * Start a simulation in the initial state of `instance`, random walks use `seed`
+     */
+    pub fn new(instance: &'a T, seed: u64) -> Self {
+        let mut labels: Vec<String> = instance.labels().into_iter().collect();
+        labels.sort();
+        Self {
+            instance,
+            labels,
+            trace: Vec::new(),
+            subformulas: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /**
+     * Load a formula, after which `holds` tells which of its closed subformulas hold in the current state.
+     *   Subformulas with free variables have no meaning on their own, so they are skipped.
+     */
+    pub fn load_formula(&mut self, f: &Formula) {
+        let mut closed: Vec<Node> = Vec::new();
+        find_closed_subformulas(&f.root_node, &mut closed);
+        self.subformulas.clear();
+        for node in closed {
+            let text = formula_to_string(&node);
+            if self.subformulas.iter().any(|(known, _)| *known == text) {
+                continue;
+            }
+            let (states, _) = execute(Formula { root_node: node }, self.instance);
+            self.subformulas.push((text, states));
+        }
+    }
+
+    /**
+     * Get the state we are in
+     */
+    pub fn current_state(&self) -> i64 {
+        self.trace.last().map_or(self.instance.initial_state(), |(_, _, target)| *target)
+    }
+
+    /**
+     * Get the outgoing transitions (label, target) of the current state, sorted so their numbering is stable
+     */
+    pub fn successors(&self) -> Vec<(String, i64)> {
+        let state = self.current_state();
+        let mut successors: Vec<(String, i64)> = Vec::new();
+        for label in &self.labels {
+            let mut targets = self.instance.successors(state, label);
+            targets.sort();
+            successors.extend(targets.into_iter().map(|target| (label.clone(), target)));
+        }
+        successors
+    }
+
+    /**
+     * Take outgoing transition `choice` of the current state, returns false if there is no such transition
+     */
+    pub fn step(&mut self, choice: usize) -> bool {
+        let source = self.current_state();
+        match self.successors().get(choice) {
+            Some((label, target)) => {
+                self.trace.push((source, label.clone(), *target));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /**
+     * Undo the last transition, returns false if we are in the initial state
+     */
+    pub fn back(&mut self) -> bool {
+        self.trace.pop().is_some()
+    }
+
+    /**
+     * Take at most `steps` random transitions, stopping early in a deadlock. Returns the number of steps taken.
+     */
+    pub fn random_walk(&mut self, steps: usize) -> usize {
+        for taken in 0..steps {
+            let nr_of_successors = self.successors().len();
+            if nr_of_successors == 0 {
+                return taken;
+            }
+            let choice = self.rng.gen_range(0..nr_of_successors);
+            self.step(choice);
+        }
+        steps
+    }
+
+    /**
+     * Restart the random number generator with `seed`
+     */
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /**
+     * Get the closed subformulas of the loaded formula and whether they hold in the current state
+     */
+    pub fn holds(&self) -> Vec<(String, bool)> {
+        let state = self.current_state();
+        self.subformulas.iter().map(|(text, states)| (text.clone(), states.contains(&state))).collect()
+    }
+
+    /**
+     * Write the trace to `file_path`, one action per line, which is the plain text trace format of mCRL2
+     */
+    pub fn save(&self, file_path: &str) -> std::io::Result<()> {
+        let mut contents = String::new();
+        for (_, label, _) in &self.trace {
+            contents.push_str(label);
+            contents.push('\n');
+        }
+        fs::write(file_path, contents)
+    }
+}
+
+/**
+ * Collect the subformulas of node without free variables, outermost first. true, false and actions are left out.
+ */
+fn find_closed_subformulas(node: &Node, closed: &mut Vec<Node>) {
+    match node {
+        Node::Variable(_) | Node::Action(_) | Node::UnaryExpr { op: _ } => return,
+        _ => {}
+    }
+    if find_free_variables(node).is_empty() {
+        closed.push(node.clone());
+    }
+    match node {
+        Node::BinaryExpr { op: _, lhs, rhs } => {
+            find_closed_subformulas(lhs, closed);
+            find_closed_subformulas(rhs, closed);
+        }
+        Node::FixPointExpr { op: _, variable: _, rhs, surrounding_binder: _ } => find_closed_subformulas(rhs, closed),
+        _ => {}
+    }
+}
+
+/**
+ * Run the interactive simulator, reading commands from `input` and writing to `output` until `quit` or the end of the input
+ */
+pub fn run_simulator<T: TransitionSystem + ?Sized, R: BufRead, W: Write>(simulator: &mut Simulator<T>, input: R, output: &mut W) -> std::io::Result<()> {
+    writeln!(output, "Type `help` for the available commands")?;
+    print_state(simulator, output)?;
+    for line in input.lines() {
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => continue,
+            ["quit"] | ["q"] => break,
+            ["help"] | ["h"] => {
+                writeln!(output, "  <n>                 take outgoing transition n")?;
+                writeln!(output, "  back                undo the last transition")?;
+                writeln!(output, "  random [k] [seed]   take k random transitions (default 1), optionally with a new seed")?;
+                writeln!(output, "  trace               print the transitions taken so far")?;
+                writeln!(output, "  save <file>         save the trace, one action per line")?;
+                writeln!(output, "  quit                stop simulating")?;
+                continue;
+            }
+            ["back"] | ["b"] => {
+                if !simulator.back() {
+                    writeln!(output, "Already in the initial state")?;
+                }
+            }
+            ["random", rest @ ..] | ["r", rest @ ..] => {
+                let steps: usize = match rest.first().map(|k| k.parse::<usize>()) {
+                    None => 1,
+                    Some(Ok(k)) => k,
+                    Some(Err(_)) => {
+                        writeln!(output, "Expected a number of steps, got {}", rest[0])?;
+                        continue;
+                    }
+                };
+                if let Some(seed) = rest.get(1) {
+                    match seed.parse::<u64>() {
+                        Ok(seed) => simulator.reseed(seed),
+                        Err(_) => {
+                            writeln!(output, "Expected a seed, got {}", seed)?;
+                            continue;
+                        }
+                    }
+                }
+                let taken = simulator.random_walk(steps);
+                if taken < steps {
+                    writeln!(output, "Deadlock after {} steps", taken)?;
+                }
+            }
+            ["trace"] | ["t"] => {
+                for (source, label, target) in &simulator.trace {
+                    writeln!(output, "  {} -{}-> {}", source, label, target)?;
+                }
+                continue;
+            }
+            ["save", file_path] | ["s", file_path] => {
+                match simulator.save(file_path) {
+                    Ok(()) => writeln!(output, "Saved a trace of {} actions to {}", simulator.trace.len(), file_path)?,
+                    Err(e) => writeln!(output, "Could not save the trace to {}: {}", file_path, e)?,
+                }
+                continue;
+            }
+            [choice] if choice.parse::<usize>().is_ok() => {
+                if !simulator.step(choice.parse::<usize>().unwrap()) {
+                    writeln!(output, "There is no transition {}", choice)?;
+                    continue;
+                }
+            }
+            _ => {
+                writeln!(output, "Unknown command '{}', type `help` for the available commands", line.trim())?;
+                continue;
+            }
+        }
+        print_state(simulator, output)?;
+    }
+    Ok(())
+}
+
+/**
+ * Print the current state, the loaded subformulas that hold in it and its outgoing transitions
+ */
+fn print_state<T: TransitionSystem + ?Sized, W: Write>(simulator: &Simulator<T>, output: &mut W) -> std::io::Result<()> {
+    writeln!(output, "\nState {} (after {} steps)", simulator.current_state(), simulator.trace.len())?;
+    for (text, holds) in simulator.holds() {
+        writeln!(output, "  [{}] {}", if holds { "holds" } else { "fails" }, text)?;
+    }
+    let successors = simulator.successors();
+    if successors.is_empty() {
+        writeln!(output, "  deadlock, no outgoing transitions")?;
+    }
+    for (i, (label, target)) in successors.iter().enumerate() {
+        writeln!(output, "  {}: {} -> {}", i, label, target)?;
+    }
+    Ok(())
+}
@@ -1,74 +1,995 @@
-use crate::types::ltl::Ltl;
+use crate::types::transition_system::TransitionSystem;
 use crate::types::formula::Formula;
 use crate::types::formula::Operator;
 use crate::types::formula::Node;
+use crate::types::formula::find_free_variables;
+use crate::types::formula::formula_to_string;
 
 use std::cmp::max;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
 
+// Specify custom type `Statistics`
+// what the solvers count while evaluating a formula: the number of fixed point iterations, the number of
+// subformulas whose result set was taken from the cache instead of evaluating them again, and the number of times
+// the improved algorithm re-examined whether a single state satisfies a subformula. If the evaluation was stopped before it
+// finished, also why, and the last approximants of the fixed points that were being calculated at that moment.
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Statistics {
+    pub iterations: i64,
+    pub cache_hits: i64,
+    pub state_updates: i64,
+    pub trace: Vec<TraceRecord>,
+    pub stopped: Option<Stop>,
+    pub approximants: Vec<Approximant>,
+}
+
+// Specify custom type `Options`
+// how execute_with_options and execute_improved_with_options evaluate a formula: the number of threads for conjunctions and
+// disjunctions whose operands are independent and for modalities over many states, what is traced of the fixed points, and
+// when to stop: after `timeout`, before starting more than `max_iterations` fixed point iterations in total, or when
+// `cancellation` is cancelled.
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub threads: usize,
+    pub trace: Trace,
+    pub timeout: Option<Duration>,
+    pub max_iterations: Option<i64>,
+    pub cancellation: CancellationToken,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { threads: 1, trace: Trace::Off, timeout: None, max_iterations: None, cancellation: CancellationToken::default() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trace {
+    Off,
+    Sizes,  // a TraceRecord for every iteration of a fixed point
+    States, // also with the approximant itself
+}
+
+// Specify custom type `TraceRecord`
+// one iteration of a fixed point: its variable, the variables of the fixed points around it from the outside in and the variable
+// itself, the number of the iteration, counted from 1 for every time the fixed point is calculated, and the approximant it gave
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceRecord {
+    pub variable: String,
+    pub path: Vec<String>,
+    pub iteration: i64,
+    pub size: usize,
+    pub states: Option<Vec<i64>>,
+}
+
+impl TraceRecord {
+    /**
+     * Write the record as a JSON object on a single line, e.g. `{"variable":"Y","path":["X","Y"],"iteration":2,"size":5}`
+     */
+    pub fn to_json(&self) -> String {
+        let path: Vec<String> = self.path.iter().map(|variable| json_string(variable)).collect();
+        let mut json = format!("{{\"variable\":{},\"path\":[{}],\"iteration\":{},\"size\":{}",
+            json_string(&self.variable), path.join(","), self.iteration, self.size);
+        if let Some(states) = &self.states {
+            let states: Vec<String> = states.iter().map(|state| state.to_string()).collect();
+            json.push_str(&format!(",\"states\":[{}]", states.join(",")));
+        }
+        json.push('}');
+        json
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stop {
+    Cancelled,
+    Timeout,
+    MaxIterations,
+}
+
+// Specify custom type `CancellationToken`
+// stops an evaluation that uses it from another thread, e.g. `let token = options.cancellation.clone();` before evaluating, then
+// `token.cancel()`. The fixed points check it before and after every iteration.
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<OnceLock<Stop>>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.stop(Stop::Cancelled);
+    }
+
+    /**
+     * Get why the token was cancelled, if it was
+     */
+    pub fn reason(&self) -> Option<Stop> {
+        self.0.get().copied()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+
+    // The first reason is kept
+    fn stop(&self, reason: Stop) {
+        let _ = self.0.set(reason);
+    }
+}
+
+// Specify custom type `Approximant`
+// the last approximant of a fixed point that was being calculated when the evaluation stopped, after `iteration` iterations.
+// Approximants of a least fixed point only grow, and those of a greatest fixed point only shrink, so for the values the
+// variables around it had, `states` is a subset of a least and a superset of a greatest fixed point. For a fixed point that
+// is not inside another one, e.g. the outermost one, that makes it a sound under- or over-approximation of the fixed point.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Approximant {
+    pub variable: String,
+    pub path: Vec<String>,
+    pub op: Operator,
+    pub iteration: i64,
+    pub states: HashSet<i64>,
+}
+
+// Specify custom type `Justification`
+// what execute_justified recorded while it evaluated a formula, to explain its result: the result sets of the subformulas
+// outside all fixed points, and every calculation of every fixed point. Subformulas are identified by their address in the formula.
+
+#[derive(Debug, Default, Clone)]
+pub struct Justification {
+    top: HashMap<usize, HashSet<i64>>,
+    calculations: HashMap<usize, Vec<Calculation>>,
+}
+
+// Specify custom type `Calculation`
+// one calculation of a fixed point by calculate_fixpoint_improved, with the values of the variables around it that it used.
+// The approximants of a least fixed point only grow and those of a greatest fixed point only shrink, and so do the sets of the
+// subformulas of its body. So for every subformula of the body we keep the iteration, counted from 1, in which it became true
+// (mu) or false (nu) in a state, its rank there. The approximant after k iterations is the body in iteration k. For a diamond
+// in a least and a box in a greatest fixed point we also keep the successor that decided it, whose rank is at most its own.
+
+#[derive(Debug, Clone)]
+pub struct Calculation {
+    op: Operator,
+    environment: Vec<(String, HashSet<i64>)>,
+    result: HashSet<i64>,
+    ranks: HashMap<usize, HashMap<i64, i64>>,
+    successors: HashMap<(usize, i64), i64>,
+}
+
+impl Justification {
+    /**
+     * Check whether state satisfies node, a subformula outside all fixed points
+     */
+    pub fn holds(&self, node: &Node, state: i64) -> bool {
+        self.top.get(&address(node)).is_some_and(|result| result.contains(&state))
+    }
+
+    /**
+     * Get the calculations of the fixed point node, in the order they were done
+     */
+    pub fn calculations(&self, node: &Node) -> &[Calculation] {
+        self.calculations.get(&address(node)).map_or(&[], |calculations| calculations.as_slice())
+    }
+
+    fn join(&mut self, other: Justification) {
+        self.top.extend(other.top);
+        for (node, calculations) in other.calculations {
+            self.calculations.entry(node).or_default().extend(calculations);
+        }
+    }
+}
+
+impl Calculation {
+    /**
+     * Start the calculation of the fixed point node, given the variables of the fixed points around it and their values
+     */
+    fn new(node: &Node, path: &[String], variable_map: &HashMap<String,HashSet<i64>>) -> Self {
+        let Node::FixPointExpr { op, variable: _, rhs: _, surrounding_binder: _ } = node else { unreachable!() };
+        let mut environment: Vec<(String, HashSet<i64>)> = find_free_variables(node).into_iter()
+            .filter(|variable| path.contains(variable))
+            .map(|variable| {
+                let value = variable_map.get(&variable).cloned().unwrap_or_default();
+                (variable, value)
+            })
+            .collect();
+        environment.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        Calculation { op: op.clone(), environment, result: HashSet::new(), ranks: HashMap::new(), successors: HashMap::new() }
+    }
+
+    /**
+     * Get the values of the variables around the fixed point it was calculated for
+     */
+    pub fn environment(&self) -> &[(String, HashSet<i64>)] {
+        &self.environment
+    }
+
+    /**
+     * Get the fixed point
+     */
+    pub fn result(&self) -> &HashSet<i64> {
+        &self.result
+    }
+
+    /**
+     * Get the iteration in which node, a subformula of the body, became true (mu) or false (nu) in state, if it did
+     */
+    pub fn rank(&self, node: &Node, state: i64) -> Option<i64> {
+        self.ranks.get(&address(node)).and_then(|ranks| ranks.get(&state)).copied()
+    }
+
+    /**
+     * Check whether state satisfies node, a subformula of the body, in the given iteration, or in the fixed point if None
+     */
+    pub fn holds(&self, node: &Node, state: i64, iteration: Option<i64>) -> bool {
+        let decided = self.rank(node, state).is_some_and(|rank| iteration.is_none_or(|iteration| rank <= iteration));
+        decided == (self.op == Operator::LeastFixpoint)
+    }
+
+    /**
+     * Get the successor that decided the modality node in state, if it is a diamond in a least or a box in a greatest fixed point
+     */
+    pub fn successor(&self, node: &Node, state: i64) -> Option<i64> {
+        self.successors.get(&(address(node), state)).copied()
+    }
+}
+
+// The budget of an evaluation, shared by all threads of it. It has a token of its own, which is cancelled when the budget runs
+// out or the token of the options is cancelled, so that running out does not cancel the options for the next evaluation.
+#[derive(Clone)]
+struct Budget {
+    cancellation: CancellationToken,
+    stopped: CancellationToken,
+    deadline: Option<Instant>,
+    max_iterations: Option<i64>,
+    iterations: Arc<AtomicI64>,
+}
+
+impl Budget {
+    fn new(options: &Options) -> Self {
+        Budget {
+            cancellation: options.cancellation.clone(),
+            stopped: CancellationToken::default(),
+            deadline: options.timeout.map(|timeout| Instant::now() + timeout),
+            max_iterations: options.max_iterations,
+            iterations: Arc::new(AtomicI64::new(0)),
+        }
+    }
+
+    /**
+     * Check whether another fixed point iteration may start, and count it if so
+     */
+    fn start_iteration(&self) -> bool {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.stopped.stop(Stop::Timeout);
+        }
+        if self.max_iterations.is_some_and(|max_iterations| self.iterations.fetch_add(1, Ordering::Relaxed) >= max_iterations) {
+            self.stopped.stop(Stop::MaxIterations);
+        }
+        !self.is_stopped()
+    }
+
+    fn is_stopped(&self) -> bool {
+        if let Some(reason) = self.cancellation.reason() {
+            self.stopped.stop(reason);
+        }
+        self.stopped.is_cancelled()
+    }
+}
+
+/**
+ * Quote a string for JSON
+ */
+fn json_string(string: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Specify custom type `Context`
+// how a formula is evaluated: the threads it may use, with the conjunctions and disjunctions whose operands can be evaluated at
+// the same time because neither binds a variable the other one uses, what is traced, the variables of the fixed points that are
+// being calculated and the budget of the evaluation. The fixed points are numbered in the order they occur in the formula, for
+// the sets execute_from starts them from and the last value of each of them. It also keeps the predecessors of the states for
+// every label, which the worklists of calculate_fixpoint_improved need, the cache of the result sets, and the justification if
+// execute_justified records one.
+
+struct Context {
+    cache: Cache,
+    predecessors: HashMap<String, Arc<HashMap<i64, Vec<i64>>>>,
+    independent: Arc<HashSet<usize>>,
+    threads: usize,
+    trace: Trace,
+    path: Vec<String>,
+    budget: Budget,
+    fixpoints: Arc<HashMap<usize, usize>>,
+    start: Arc<HashMap<usize, HashSet<i64>>>,
+    values: HashMap<usize, HashSet<i64>>,
+    justification: Option<Justification>,
+}
+
+// Specify custom type `Cache`
+// the result sets of the subformulas that do not depend on the innermost fixed point around them, e.g. `<plato>true` inside
+// `mu Y`, or `<i>X` inside `mu Y` inside `nu X`. A result set is stored with the values of the free variables of the subformula,
+// and reused as long as these variables have the same values. Subformulas are identified by their address in the formula.
+// The result sets of closed subformulas are also kept by their text, for identical subformulas of this and other formulas.
+
+struct Cache {
+    free_variables: Arc<HashMap<usize, Vec<String>>>,
+    entries: HashMap<usize, (Vec<HashSet<i64>>, HashSet<i64>)>,
+    closed: Arc<HashMap<usize, String>>,
+    shared: Arc<Mutex<HashMap<String, HashSet<i64>>>>,
+}
+
+// The address of a subformula, which identifies it in the cache
+fn address(node: &Node) -> usize {
+    node as *const Node as usize
+}
+
+impl Context {
+    /**
+     * Make a context to evaluate node with options
+     */
+    fn new(node: &Node, options: &Options) -> Self {
+        let mut independent = HashSet::new();
+        Context::find_independent(node, &mut independent);
+        let fixpoints = find_fixpoints(node).into_iter()
+            .enumerate()
+            .map(|(index, fixpoint)| (address(fixpoint), index))
+            .collect();
+        Context {
+            cache: Cache::new(node),
+            predecessors: HashMap::new(),
+            independent: Arc::new(independent),
+            threads: max(options.threads, 1),
+            trace: options.trace,
+            path: Vec::new(),
+            budget: Budget::new(options),
+            fixpoints: Arc::new(fixpoints),
+            start: Arc::new(HashMap::new()),
+            values: HashMap::new(),
+            justification: None,
+        }
+    }
+
+    /**
+     * Find the conjunctions and disjunctions in node whose operands are both worth a thread, where neither binds a variable the other uses.
+     *   Returns the variables that node binds and the variables it uses.
+     */
+    fn find_independent(node: &Node, independent: &mut HashSet<usize>) -> (HashSet<String>, HashSet<String>) {
+        match node {
+            Node::Variable(variable) => (HashSet::new(), HashSet::from([variable.clone()])),
+            Node::BinaryExpr { op, lhs, rhs } => {
+                let (mut bound, mut used) = Context::find_independent(lhs, independent);
+                let (rhs_bound, rhs_used) = Context::find_independent(rhs, independent);
+                let worth_a_thread = |operand: &Node| matches!(operand, Node::BinaryExpr { .. } | Node::FixPointExpr { .. });
+                if (*op == Operator::Conjunction || *op == Operator::Disjunction) && worth_a_thread(lhs) && worth_a_thread(rhs)
+                    && bound.is_disjoint(&rhs_used) && rhs_bound.is_disjoint(&used) {
+                    independent.insert(address(node));
+                }
+                bound.extend(rhs_bound);
+                used.extend(rhs_used);
+                (bound, used)
+            }
+            Node::FixPointExpr { op: _, variable, rhs, surrounding_binder: _ } => {
+                let (mut bound, mut used) = Context::find_independent(rhs, independent);
+                bound.insert(variable.clone());
+                used.insert(variable.clone());
+                (bound, used)
+            }
+            _ => (HashSet::new(), HashSet::new()),
+        }
+    }
+
+    /**
+     * Make a context to evaluate node on another thread with `threads` threads, which takes the cache entries of the subformulas of node
+     */
+    fn fork(&mut self, node: &Node, threads: usize) -> Self {
+        Context {
+            cache: self.cache.fork(node),
+            predecessors: self.predecessors.clone(),
+            independent: self.independent.clone(),
+            threads,
+            trace: self.trace,
+            path: self.path.clone(),
+            budget: self.budget.clone(),
+            fixpoints: self.fixpoints.clone(),
+            start: self.start.clone(),
+            values: HashMap::new(),
+            justification: self.justification.as_ref().map(|_| Justification::default()),
+        }
+    }
+
+    /**
+     * Take back the cache entries, fixed point values and justification of a forked context, and the predecessors it collected
+     */
+    fn join(&mut self, other: Context) {
+        self.cache.join(other.cache);
+        self.values.extend(other.values);
+        if let (Some(justification), Some(other)) = (self.justification.as_mut(), other.justification) {
+            justification.join(other);
+        }
+        for (label, predecessors) in other.predecessors {
+            self.predecessors.entry(label).or_insert(predecessors);
+        }
+    }
+
+    /**
+     * Trace an iteration of the innermost fixed point that is being calculated, if we trace
+     */
+    fn record(&self, statistics: &mut Statistics, iteration: i64, approximant: &HashSet<i64>) {
+        if self.trace == Trace::Off {
+            return;
+        }
+        let states = (self.trace == Trace::States).then(|| {
+            let mut states: Vec<i64> = approximant.iter().cloned().collect();
+            states.sort();
+            states
+        });
+        statistics.trace.push(TraceRecord {
+            variable: self.path.last().cloned().unwrap_or_default(),
+            path: self.path.clone(),
+            iteration,
+            size: approximant.len(),
+            states,
+        });
+    }
+
+    /**
+     * Keep the last approximant of the innermost fixed point that is being calculated, which was stopped after `iteration` iterations
+     */
+    fn stop(&self, statistics: &mut Statistics, op: &Operator, iteration: i64, approximant: &HashSet<i64>) {
+        statistics.approximants.push(Approximant {
+            variable: self.path.last().cloned().unwrap_or_default(),
+            path: self.path.clone(),
+            op: op.clone(),
+            iteration,
+            states: approximant.clone(),
+        });
+    }
+
+    /**
+     * Get the set the fixed point node starts from, if execute_from was given one
+     */
+    fn start_of(&self, node: &Node) -> Option<HashSet<i64>> {
+        self.fixpoints.get(&address(node)).and_then(|index| self.start.get(index)).cloned()
+    }
+
+    /**
+     * Keep the value of node if it is a fixed point, the last one is its value for the result
+     */
+    fn keep_value(&mut self, node: &Node, result: &HashSet<i64>) {
+        if let Some(index) = self.fixpoints.get(&address(node)) {
+            self.values.insert(*index, result.clone());
+        }
+    }
+
+    /**
+     * Record the result set of node in the justification, if we keep one and node is outside all fixed points
+     */
+    fn justify(&mut self, node: &Node, result: &HashSet<i64>) {
+        if let Some(justification) = self.justification.as_mut() {
+            if self.path.is_empty() {
+                justification.top.insert(address(node), result.clone());
+            }
+        }
+    }
+
+    /**
+     * Store the result set of node in the cache. After the budget ran out results may be approximants, which should not end up
+     *   in other formulas.
+     */
+    fn insert(&mut self, node: &Node, values: Option<Vec<HashSet<i64>>>, result: &HashSet<i64>) {
+        let share = !self.budget.is_stopped();
+        self.cache.insert(node, values, result, share);
+    }
+
+    /**
+     * Get the states that reach state with one label-transition, the first time a label is used its predecessors are collected
+     */
+    fn predecessors<T: TransitionSystem + ?Sized>(&mut self, instance: &T, label: &str, state: i64) -> &[i64] {
+        let predecessors = self.predecessors.entry(label.to_string()).or_insert_with(|| {
+            let mut predecessors: HashMap<i64, Vec<i64>> = HashMap::new();
+            for source in instance.states() {
+                for target in instance.successors(source, label) {
+                    predecessors.entry(target).or_default().push(source);
+                }
+            }
+            Arc::new(predecessors)
+        });
+        predecessors.get(&state).map_or(&[], |sources| sources.as_slice())
+    }
+}
+
+impl Cache {
+    /**
+     * Make a cache for the subformulas of node that do not depend on the innermost fixed point around them
+     */
+    fn new(node: &Node) -> Self {
+        let mut free_variables = HashMap::new();
+        Cache::find_cacheable(node, None, &mut free_variables);
+        let mut closed = HashMap::new();
+        Cache::find_closed(node, &mut closed);
+        Cache {
+            free_variables: Arc::new(free_variables),
+            entries: HashMap::new(),
+            closed: Arc::new(closed),
+            shared: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn find_cacheable(node: &Node, innermost: Option<&str>, free_variables: &mut HashMap<usize, Vec<String>>) {
+        match node {
+            Node::BinaryExpr { op: _, lhs, rhs } => {
+                Cache::find_cacheable(lhs, innermost, free_variables);
+                Cache::find_cacheable(rhs, innermost, free_variables);
+            }
+            Node::FixPointExpr { op: _, variable, rhs, surrounding_binder: _ } => Cache::find_cacheable(rhs, Some(variable), free_variables),
+            // Variables, true and false are not worth caching
+            _ => return,
+        }
+        // Outside all fixed points a subformula is evaluated only once
+        if let Some(innermost) = innermost {
+            let free: HashSet<String> = find_free_variables(node);
+            if !free.contains(innermost) {
+                let mut free: Vec<String> = free.into_iter().collect();
+                free.sort();
+                free_variables.insert(address(node), free);
+            }
+        }
+    }
+
+    /**
+     * Find the subformulas of node without free variables that are worth sharing, with their text.
+     *   Returns the free variables of node.
+     */
+    fn find_closed(node: &Node, closed: &mut HashMap<usize, String>) -> HashSet<String> {
+        let free: HashSet<String> = match node {
+            Node::BinaryExpr { op: _, lhs, rhs } => {
+                let mut free = Cache::find_closed(lhs, closed);
+                free.extend(Cache::find_closed(rhs, closed));
+                free
+            }
+            Node::FixPointExpr { op: _, variable, rhs, surrounding_binder: _ } => {
+                let mut free = Cache::find_closed(rhs, closed);
+                free.remove(variable);
+                free
+            }
+            // Variables, true and false are not worth sharing
+            Node::Variable(variable) => return HashSet::from([variable.clone()]),
+            Node::Action(_) | Node::UnaryExpr { op: _ } => return HashSet::new(),
+        };
+        if free.is_empty() {
+            closed.insert(address(node), formula_to_string(node));
+        }
+        free
+    }
+
+    /**
+     * Get the current values of the free variables of node, if it is cached
+     */
+    fn values_of(&self, node: &Node, variable_map: &HashMap<String,HashSet<i64>>) -> Option<Vec<HashSet<i64>>> {
+        self.free_variables.get(&address(node))
+            .map(|free| free.iter().map(|variable| variable_map.get(variable).cloned().unwrap_or_default()).collect())
+    }
+
+    /**
+     * Get the result set of node if it was evaluated for these values of its free variables before.
+     *   Evaluating a fixed point leaves its variable at the result, so on a hit we do the same.
+     */
+    fn get(&self, node: &Node, values: &Option<Vec<HashSet<i64>>>, variable_map: &mut HashMap<String,HashSet<i64>>) -> Option<HashSet<i64>> {
+        let result = match self.entries.get(&address(node)) {
+            Some((cached_values, result)) if values.as_ref() == Some(cached_values) => result.clone(),
+            _ => {
+                let text = self.closed.get(&address(node))?;
+                self.shared.lock().expect("A thread sharing result sets should not panic").get(text)?.clone()
+            }
+        };
+        if let Node::FixPointExpr { op: _, variable, rhs: _, surrounding_binder: _ } = node {
+            variable_map.insert(variable.clone(), result.clone());
+        }
+        Some(result)
+    }
+
+    fn insert(&mut self, node: &Node, values: Option<Vec<HashSet<i64>>>, result: &HashSet<i64>, share: bool) {
+        if let Some(values) = values {
+            self.entries.insert(address(node), (values, result.clone()));
+        }
+        if let Some(text) = self.closed.get(&address(node)) {
+            if share {
+                self.shared.lock().expect("A thread sharing result sets should not panic").insert(text.clone(), result.clone());
+            }
+        }
+    }
+
+    /**
+     * Make a cache for another thread evaluating node, which takes the entries of the subformulas of node
+     */
+    fn fork(&mut self, node: &Node) -> Self {
+        let mut subformulas: Vec<&Node> = Vec::new();
+        collect_subformulas(node, &mut subformulas);
+        let entries = subformulas.into_iter()
+            .filter_map(|subformula| self.entries.remove_entry(&address(subformula)))
+            .collect();
+        Cache {
+            free_variables: self.free_variables.clone(),
+            entries,
+            closed: self.closed.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+
+    /**
+     * Take back the entries of a forked cache
+     */
+    fn join(&mut self, other: Cache) {
+        self.entries.extend(other.entries);
+    }
+}
+
+/**
+ * Collect node and all its subformulas
+ */
+fn collect_subformulas<'a>(node: &'a Node, subformulas: &mut Vec<&'a Node>) {
+    subformulas.push(node);
+    match node {
+        Node::BinaryExpr { op: _, lhs, rhs } => {
+            collect_subformulas(lhs, subformulas);
+            collect_subformulas(rhs, subformulas);
+        }
+        Node::FixPointExpr { op: _, variable: _, rhs, surrounding_binder: _ } => collect_subformulas(rhs, subformulas),
+        _ => {}
+    }
+}
+
+/**
+ * Get the fixed points in node in the order they occur, which is how execute_from numbers them
+ */
+pub fn find_fixpoints(node: &Node) -> Vec<&Node> {
+    let mut subformulas: Vec<&Node> = Vec::new();
+    collect_subformulas(node, &mut subformulas);
+    subformulas.retain(|subformula| matches!(subformula, Node::FixPointExpr { .. }));
+    subformulas
+}
+
+// Below this many states a modality is not split over threads
+const STATES_PER_THREAD: usize = 1 << 12;
+
+/**
+ * Get [[ <a>f ]] or [[ [a]f ]] for the modality op, where the states are split into chunks that are checked by `threads` threads
+ *   if there are enough of them.
+ */
+fn get_modality<T: TransitionSystem + ?Sized>(op: &Operator, label: &str, out_states: HashSet<i64>, instance: &T, threads: usize) -> HashSet<i64> {
+    let states = instance.states();
+    if threads < 2 || states.len() < 2 * STATES_PER_THREAD {
+        return if *op == Operator::DiamondModality {
+            instance.get_diamond_modality(label.to_string(), out_states)
+        } else {
+            instance.get_box_modality(label.to_string(), out_states)
+        };
+    }
+    let chunk_size = max(states.len().div_ceil(threads), STATES_PER_THREAD);
+    let holds = |state: &i64| {
+        let successors = instance.successors(*state, label);
+        if *op == Operator::DiamondModality {
+            successors.iter().any(|successor| out_states.contains(successor))
+        } else {
+            successors.iter().all(|successor| out_states.contains(successor))
+        }
+    };
+    thread::scope(|scope| {
+        let chunks: Vec<_> = states.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().filter(|state| holds(state)).cloned().collect::<Vec<i64>>()))
+            .collect();
+        chunks.into_iter().flat_map(|chunk| chunk.join().expect("A thread checking a modality should not panic")).collect()
+    })
+}
+
+/**
+ * Evaluate the operands lhs and rhs of a conjunction or disjunction node with eval. If the context allows it, rhs is evaluated on
+ *   another thread, with its own copy of the variables, statistics and context, and half of the threads. Neither operand binds a
+ *   variable the other one uses, so afterwards we take over the variables that rhs binds, and the result is the same as evaluating
+ *   lhs and then rhs.
+ */
+fn eval_operands<T, E>(node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>, statistics: &mut Statistics,
+    context: &mut Context, eval: E) -> (HashSet<i64>, HashSet<i64>)
+where
+    T: TransitionSystem + ?Sized,
+    E: Fn(&Node, &T, &mut HashMap<String,HashSet<i64>>, &mut Statistics, &mut Context) -> HashSet<i64> + Sync,
+{
+    let Node::BinaryExpr { op: _, lhs, rhs } = node else { unreachable!() };
+    if context.threads < 2 || !context.independent.contains(&address(node)) {
+        let eval_lhs = eval(lhs, instance, variable_map, statistics, context);
+        let eval_rhs = eval(rhs, instance, variable_map, statistics, context);
+        return (eval_lhs, eval_rhs);
+    }
+    let rhs_threads = context.threads / 2;
+    context.threads -= rhs_threads;
+    let mut rhs_variable_map = variable_map.clone();
+    let mut rhs_statistics = Statistics::default();
+    let mut rhs_context = context.fork(rhs, rhs_threads);
+    let (eval_lhs, eval_rhs) = thread::scope(|scope| {
+        let eval = &eval;
+        let eval_rhs = scope.spawn(|| eval(rhs, instance, &mut rhs_variable_map, &mut rhs_statistics, &mut rhs_context));
+        let eval_lhs = eval(lhs, instance, variable_map, statistics, context);
+        (eval_lhs, eval_rhs.join().expect("A thread evaluating a subformula should not panic"))
+    });
+    context.threads += rhs_threads;
+    context.join(rhs_context);
+    statistics.iterations += rhs_statistics.iterations;
+    statistics.cache_hits += rhs_statistics.cache_hits;
+    statistics.state_updates += rhs_statistics.state_updates;
+    statistics.trace.extend(rhs_statistics.trace);
+    statistics.approximants.extend(rhs_statistics.approximants);
+    let mut subformulas: Vec<&Node> = Vec::new();
+    collect_subformulas(rhs, &mut subformulas);
+    for subformula in subformulas {
+        if let Node::FixPointExpr { op: _, variable, rhs: _, surrounding_binder: _ } = subformula {
+            if let Some(value) = rhs_variable_map.remove(variable) {
+                variable_map.insert(variable.clone(), value);
+            }
+        }
+    }
+    (eval_lhs, eval_rhs)
+}
+
+// Specify custom type `Worklist`
+// the sets of the subformulas of the body of a fixed point in the current iteration of calculate_fixpoint_improved. When the
+// approximant of the variable changes on some states, only the states whose value may change are re-examined: those of a
+// conjunction or disjunction where an operand changed, and the predecessors of the changed states of the operand of a modality.
+// Subformulas without the variable never change, fixed points with the variable are evaluated again in full.
+// For execute_justified it also records the calculation, where `iteration` is the current iteration.
+
+struct Worklist<'v> {
+    variable: &'v str,
+    values: HashMap<*const Node, HashSet<i64>>,
+    dependent: HashSet<*const Node>,
+    changed: HashSet<i64>,
+    iteration: i64,
+    calculation: Option<Calculation>,
+}
+
+impl<'v> Worklist<'v> {
+    fn new(variable: &'v str) -> Self {
+        Worklist { variable, values: HashMap::new(), dependent: HashSet::new(), changed: HashSet::new(), iteration: 0, calculation: None }
+    }
+
+    /**
+     * Record that node became true (mu) or false (nu) on states in the current iteration, if we record the calculation
+     */
+    fn decide<T: TransitionSystem + ?Sized>(&mut self, node: &Node, states: &HashSet<i64>, instance: &T) {
+        let iteration = self.iteration;
+        let Some(calculation) = self.calculation.as_mut() else { return };
+        for state in states {
+            calculation.ranks.entry(address(node)).or_default().entry(*state).or_insert(iteration);
+            // The first successor that was decided as well
+            if let Node::BinaryExpr { op, lhs, rhs } = node {
+                if let Node::Action(label) = &**lhs {
+                    if (*op == Operator::DiamondModality) == (calculation.op == Operator::LeastFixpoint) {
+                        let mut targets = instance.successors(*state, label);
+                        targets.sort();
+                        let target = targets.into_iter()
+                            .find(|target| calculation.rank(rhs, *target).is_some_and(|rank| rank <= iteration))
+                            .expect("A decided modality should have a decided successor");
+                        calculation.successors.insert((address(node), *state), target);
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+     * Evaluate node for the first iteration and remember the sets of the subformulas that depend on the variable
+     */
+    fn init<T: TransitionSystem + ?Sized>(&mut self, node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>,
+        variables_open_map: &HashMap<String, HashSet<String>>, statistics: &mut Statistics, context: &mut Context) -> HashSet<i64> {
+        // A recorded calculation needs the ranks of all subformulas
+        let result = match node {
+            Node::BinaryExpr { op, lhs, rhs } if self.calculation.is_some() || find_free_variables(node).contains(self.variable) => {
+                let lhs_states = match &**lhs {
+                    Node::Action(_) => HashSet::new(),
+                    _ => self.init(lhs, instance, variable_map, variables_open_map, statistics, context),
+                };
+                let rhs_states = self.init(rhs, instance, variable_map, variables_open_map, statistics, context);
+                match (op, &**lhs) {
+                    (Operator::DiamondModality | Operator::BoxModality, Node::Action(label)) => get_modality(op, label, rhs_states, instance, context.threads),
+                    (Operator::Conjunction, _) => lhs_states.intersection(&rhs_states).cloned().collect(),
+                    _ => lhs_states.union(&rhs_states).cloned().collect(),
+                }
+            }
+            _ => eval_improved(node, instance, variable_map, variables_open_map, statistics, context),
+        };
+        if find_free_variables(node).contains(self.variable) {
+            self.dependent.insert(node);
+        }
+        self.values.insert(node, result.clone());
+        if self.calculation.as_ref().is_some_and(|calculation| calculation.op == Operator::LeastFixpoint) {
+            self.decide(node, &result, instance);
+        } else if self.calculation.is_some() {
+            let decided = instance.get_all_states().difference(&result).cloned().collect();
+            self.decide(node, &decided, instance);
+        }
+        result
+    }
+
+    /**
+     * Update the sets of node and its subformulas after the variable changed on the states `self.changed`,
+     *   and return the states on which the set of node changed.
+     */
+    fn update<T: TransitionSystem + ?Sized>(&mut self, node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>,
+        variables_open_map: &HashMap<String, HashSet<String>>, statistics: &mut Statistics, context: &mut Context) -> HashSet<i64> {
+        if !self.dependent.contains(&(node as *const Node)) {
+            return HashSet::new();
+        }
+        let candidates: HashSet<i64> = match node {
+            Node::Variable(_) => {
+                self.values.insert(node, variable_map[self.variable].clone());
+                let changed = self.changed.clone();
+                self.decide(node, &changed, instance);
+                return changed;
+            }
+            Node::FixPointExpr { .. } => {
+                let result = eval_improved(node, instance, variable_map, variables_open_map, statistics, context);
+                let old = self.values.insert(node, result.clone()).unwrap_or_default();
+                let flipped = old.symmetric_difference(&result).cloned().collect();
+                self.decide(node, &flipped, instance);
+                return flipped;
+            }
+            Node::BinaryExpr { op: _, lhs, rhs } => match &**lhs {
+                Node::Action(label) => self.update(rhs, instance, variable_map, variables_open_map, statistics, context).iter()
+                    .flat_map(|state| context.predecessors(instance, label, *state).to_vec())
+                    .collect(),
+                _ => {
+                    let mut candidates = self.update(lhs, instance, variable_map, variables_open_map, statistics, context);
+                    candidates.extend(self.update(rhs, instance, variable_map, variables_open_map, statistics, context));
+                    candidates
+                }
+            },
+            _ => HashSet::new(),
+        };
+
+        // Re-examine the candidates with the new sets of the operands
+        let Node::BinaryExpr { op, lhs, rhs } = node else { unreachable!() };
+        let contains = |operand: &Node, state: &i64| self.values[&(operand as *const Node)].contains(state);
+        let mut flipped: Vec<i64> = Vec::new();
+        for state in candidates {
+            statistics.state_updates += 1;
+            let holds = match (op, &**lhs) {
+                (Operator::DiamondModality, Node::Action(label)) => instance.successors(state, label).iter().any(|target| contains(rhs, target)),
+                (Operator::BoxModality, Node::Action(label)) => instance.successors(state, label).iter().all(|target| contains(rhs, target)),
+                (Operator::Conjunction, _) => contains(lhs, &state) && contains(rhs, &state),
+                _ => contains(lhs, &state) || contains(rhs, &state),
+            };
+            if holds != self.values[&(node as *const Node)].contains(&state) {
+                flipped.push(state);
+            }
+        }
+        let values = self.values.get_mut(&(node as *const Node)).unwrap();
+        for state in flipped.iter() {
+            if !values.remove(state) {
+                values.insert(*state);
+            }
+        }
+        let flipped = flipped.into_iter().collect();
+        self.decide(node, &flipped, instance);
+        flipped
+    }
+}
+
 /**
- * Given a Formula f and an LTL instance, evaluate f on the instance.
- * Uses the eval functions and also returns the number of iterations.
+ * Given a Formula f and a transition system instance (e.g. an LTL), evaluate f on the instance.
+ * Uses the eval functions and also returns the statistics, i.e. the number of iterations and cache hits.
  */
-pub fn execute(f: Formula, instance:Ltl) -> (HashSet<i64>, i64) {
-    // Make a new map and iterations variable and call the eval function.
+pub fn execute<T: TransitionSystem + ?Sized>(f: Formula, instance: &T) -> (HashSet<i64>, Statistics) {
+    execute_with_options(f, instance, &Options::default())
+}
+
+/**
+ * Evaluate f on the instance as execute does, with the threads and tracing of options.
+ *   The result and the statistics do not depend on the number of threads.
+ */
+pub fn execute_with_options<T: TransitionSystem + ?Sized>(f: Formula, instance: &T, options: &Options) -> (HashSet<i64>, Statistics) {
+    let (result, statistics, _) = execute_from(&f, instance, options, &HashMap::new(), &mut HashMap::new());
+    (result, statistics)
+}
+
+/**
+ * Evaluate f on the instance as execute_with_options does, but the k-th fixed point of f (counted from 0 in the order they occur)
+ *   starts from start[k] instead of from the empty set or all states, if it is in start. That gives the same result if start[k]
+ *   is a subset of f(start[k]) and of the least fixed point, or a superset of f(start[k]) and of the greatest fixed point, every
+ *   time the fixed point is calculated. Also returns the last value of every fixed point.
+ *   A closed subformula whose text is in shared takes its result set from there, which counts as a cache hit, and the result
+ *   sets of the other closed subformulas are added to it. So it can be passed on to the next formula on the same instance.
+ */
+pub fn execute_from<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T, options: &Options, start: &HashMap<usize, HashSet<i64>>,
+    shared: &mut HashMap<String, HashSet<i64>>) -> (HashSet<i64>, Statistics, HashMap<usize, HashSet<i64>>) {
+    // Make a new map, statistics and context and call the eval function.
     let mut variable_map: HashMap<String,HashSet<i64>> = HashMap::new();
-    let mut iterations: i64 = 0;
-    (eval(f.root_node, &instance, &mut variable_map, &mut iterations), iterations)    
+    let mut statistics = Statistics::default();
+    let mut context = Context::new(&f.root_node, options);
+    context.start = Arc::new(start.clone());
+    context.cache.shared = Arc::new(Mutex::new(std::mem::take(shared)));
+    let result = eval(&f.root_node, instance, &mut variable_map, &mut statistics, &mut context);
+    statistics.stopped = context.budget.stopped.reason();
+    *shared = std::mem::take(&mut *context.cache.shared.lock().expect("A thread sharing result sets should not panic"));
+    (result, statistics, context.values)
 }
 
 /**
  * Given a Node node and an LTL instance evaluate the set of stats in instance satisfing satisfying the formula represented by node.
- * also requires variable_map, found fixed point variabels and their associated value and iterations, 
- * to keep track of the number of fixed point iterations.
+ * also requires variable_map, found fixed point variabels and their associated value, statistics, to keep track of the number of
+ * fixed point iterations and cache hits, and the context with the cache of result sets of subformulas.
+ */
+fn eval<T: TransitionSystem + ?Sized>(node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>, statistics: &mut Statistics,
+    context: &mut Context) -> HashSet<i64> {
+    // A subformula that does not depend on the fixed point around it may already have been evaluated for the current values:
+    let values = context.cache.values_of(node, variable_map);
+    if let Some(result) = context.cache.get(node, &values, variable_map) {
+        statistics.cache_hits += 1;
+        context.keep_value(node, &result);
+        return result;
+    }
+    let result = eval_node(node, instance, variable_map, statistics, context);
+    context.insert(node, values, &result);
+    context.keep_value(node, &result);
+    result
+}
+
+/**
+ * Evaluate node itself for eval, where its subformulas are evaluated with eval.
  */
-fn eval(node: Node, instance:&Ltl, variable_map: &mut HashMap<String,HashSet<i64>>, iterations: &mut i64) -> HashSet<i64> {
+fn eval_node<T: TransitionSystem + ?Sized>(node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>, statistics: &mut Statistics,
+    context: &mut Context) -> HashSet<i64> {
     match node {
         Node::Variable(string) => {
             // The first case, we need to return the value associated with the variable string in the variable_map.
-            // If this value exists, return the associated value:
-            if (*variable_map).contains_key(&string.clone()) {
-                return variable_map.get(&string.clone()).unwrap().clone();
-            } else {
-                // Otherwise we need to insert an empty set into the map, and return this empty set.
-                (*variable_map).insert(string.clone(), HashSet::new());
-                return variable_map.get(&string.clone()).unwrap().clone();
-            }
+            // If this value does not exist, we insert an empty set into the map and return this empty set:
+            variable_map.entry(string.clone()).or_default().clone()
         }
         Node::BinaryExpr { op, lhs, rhs } => {
             // We have a binary expression, that is an expression over 2 variables (conjunction/disjunction/boxmodality/diamondmodality):
-            if op == Operator::Conjunction {
+            if *op == Operator::Conjunction {
                 // We have a conjunction, we return the intersection of the evaluation of the left and right hand side:
-                let eval_lhs = eval(*lhs, instance, variable_map, iterations);
-                let eval_rhs = eval(*rhs, instance, variable_map, iterations);
-                return eval_lhs.intersection(&eval_rhs).map(|x| *x).collect::<HashSet<i64>>();
-            } else if op == Operator::Disjunction {
+                let (eval_lhs, eval_rhs) = eval_operands(node, instance, variable_map, statistics, context, eval);
+                eval_lhs.intersection(&eval_rhs).copied().collect::<HashSet<i64>>()
+            } else if *op == Operator::Disjunction {
                 // We have a disjunction, we return the union of the evaluation of the left and right hand side:
-                let eval_lhs = eval(*lhs, instance, variable_map, iterations);
-                let eval_rhs = eval(*rhs, instance, variable_map, iterations);
-                return eval_lhs.union(&eval_rhs).map(|x| *x).collect::<HashSet<i64>>();
-            } else if op == Operator::DiamondModality {
+                let (eval_lhs, eval_rhs) = eval_operands(node, instance, variable_map, statistics, context, eval);
+                eval_lhs.union(&eval_rhs).copied().collect::<HashSet<i64>>()
+            } else if *op == Operator::DiamondModality {
                 // We have a diamondmodality, we get the action label and call the get_diamond_modality function 
                 // on the LTL instance to get the diamond modality.
-                let node = *lhs;
-                match node {
+                match &**lhs {
                     Node::Action(string) => {
-                        let states_rhs: HashSet<i64> = eval(*rhs, instance, variable_map, iterations);
-                        return instance.get_diamond_modality(string, states_rhs)
+                        let states_rhs: HashSet<i64> = eval(rhs, instance, variable_map, statistics, context);
+                        get_modality(op, string, states_rhs, instance, context.threads)
                     }
                     Node::Variable(_) | Node::UnaryExpr { op: _ } | Node::BinaryExpr { op: _, lhs: _, rhs: _ } | 
                     Node::FixPointExpr { op: _, variable: _, rhs: _, surrounding_binder: _ } => unreachable!(),
                 }
-            } else if op == Operator::BoxModality {
+            } else if *op == Operator::BoxModality {
                 // We have a boxmodality, we get the action label and call the get_box_modality function 
                 // on the LTL instance to get the diamond modality.
-                let node = *lhs;
-                match node {
+                match &**lhs {
                     Node::Action(string) => {
-                        let states_rhs: HashSet<i64> = eval(*rhs, instance, variable_map, iterations);
-                        return instance.get_box_modality(string, states_rhs)
+                        let states_rhs: HashSet<i64> = eval(rhs, instance, variable_map, statistics, context);
+                        get_modality(op, string, states_rhs, instance, context.threads)
                     }
                     Node::Variable(_) | Node::UnaryExpr { op: _ } | Node::BinaryExpr { op: _, lhs: _, rhs: _ } | 
                     Node::FixPointExpr { op: _, variable: _, rhs: _, surrounding_binder: _ } => unreachable!(),
@@ -79,28 +1000,29 @@ fn eval(node: Node, instance:&Ltl, variable_map: &mut HashMap<String,HashSet<i64
         }
         Node::UnaryExpr { op } => {
             // We have a unary expression, that is either a simple true or a simple false statement.
-            if op == Operator::SimpleTrue {
+            if *op == Operator::SimpleTrue {
                 // If we have a simple true, we simple return all states from instance using instance.get_all_states().
-                let all_states = instance.get_all_states();
-                return all_states;
-            } else if op == Operator::SimpleFalse {
+                instance.get_all_states()
+            } else if *op == Operator::SimpleFalse {
                 // We have a simple false, we simply return an empty set.
-                return HashSet::new();
+                HashSet::new()
             } else {
                 panic!("This should not reach any statement except true or false");
             }
         }
-        Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } => {
+        Node::FixPointExpr { op, variable, rhs: _, surrounding_binder: _ } => {
             // We have a fixed point expression, we first check wheter we have a least fixed point or greatest fixed point.
             // Then in the case of a greatest fixed point, we set variable_map[operator] to the set of all states in the instance.
             // In the case of a least fixed point, we set variable_map[operator] to the empty set. Then we use calculate_fixpoint
             // to calculate the fixed point.
-            if op == Operator::GreatestFixpoint {
-                (*variable_map).insert(variable.clone(), instance.get_all_states());
-                return calculate_fixpoint(variable, *rhs, instance, variable_map, iterations)
-            } else if op == Operator::LeastFixpoint {
-                (*variable_map).insert(variable.clone(), HashSet::new());
-                return calculate_fixpoint(variable, *rhs, instance, variable_map, iterations)
+            // Unless execute_from gave another set to start from.
+            if *op == Operator::GreatestFixpoint {
+                let start = context.start_of(node).unwrap_or_else(|| instance.get_all_states());
+                (*variable_map).insert(variable.clone(), start);
+                calculate_fixpoint(node, instance, variable_map, statistics, context)
+            } else if *op == Operator::LeastFixpoint {
+                (*variable_map).insert(variable.clone(), context.start_of(node).unwrap_or_default());
+                calculate_fixpoint(node, instance, variable_map, statistics, context)
             } else {
                 panic!("This should not happen");
             }
@@ -112,45 +1034,95 @@ fn eval(node: Node, instance:&Ltl, variable_map: &mut HashMap<String,HashSet<i64
 }
 
 /**
- * Given a variable string, a Node g, an LTL instance, a variable_map and an iterations variable,
- * calculate the fixed point of g for variable string and return the resulting set.
+ * Given a fixed point Node node binding variable in its body g, an LTL instance, a variable_map, statistics and a context,
+ * calculate the fixed point of g for variable and return the resulting set.
+ * If the budget of the context runs out, return the last approximant instead.
  */
-fn calculate_fixpoint(variable: String, g: Node, instance:&Ltl, variable_map: &mut HashMap<String,HashSet<i64>>, iterations: &mut i64) -> HashSet<i64> {
-    // Set x' to something that is both not the full set and the empty set, to make sure we do not quit immediately:
-    let mut x_prime: HashSet<i64> = HashSet::from([1]); 
+fn calculate_fixpoint<T: TransitionSystem + ?Sized>(node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>, statistics: &mut Statistics,
+    context: &mut Context) -> HashSet<i64> {
+    let Node::FixPointExpr { op, variable, rhs: g, surrounding_binder: _ } = node else { unreachable!() };
+    // There is no x' yet, to make sure we do at least one iteration, also when the fixed point starts from a given value:
+    let mut x_prime: Option<HashSet<i64>> = None;
     // Retrieve the a value from the map:
     let mut a = variable_map.get(&variable.clone()).unwrap().clone();
+    context.path.push(variable.clone());
+    let mut iteration: i64 = 0;
     // Until x' is equal to a, we keep calculating the fixed point using the eval function and insert the result into the map.
-    while x_prime != a {
-        x_prime = a.clone();
-        let temp = eval(g.clone(), instance, variable_map, iterations);
+    while x_prime.as_ref() != Some(&a) {
+        // Stop before the iteration if the budget ran out, unless nothing was calculated yet:
+        if !context.budget.start_iteration() {
+            if iteration > 0 {
+                context.stop(statistics, op, iteration, &a);
+            }
+            break;
+        }
+        x_prime = Some(a.clone());
+        let temp = eval(g, instance, variable_map, statistics, context);
+        // If it ran out during the iteration, g was evaluated with approximants of the fixed points in it, so we keep the last one:
+        if context.budget.is_stopped() {
+            context.stop(statistics, op, iteration, &a);
+            break;
+        }
         (*variable_map).insert(variable.clone(), temp);
         a = variable_map.get(&variable.clone()).unwrap().clone();
         // Each iteration of the while loop, we increment iterations by 1:
-        *iterations += 1;
+        statistics.iterations += 1;
+        iteration += 1;
+        context.record(statistics, iteration, &a);
     }
+    context.path.pop();
     // Return the value at variable_map[variable].
-    return variable_map.get(&variable.clone()).unwrap().clone();
+    variable_map.get(&variable.clone()).unwrap().clone()
 }
 
 /**
- * Given a Formula f and an LTL instance, evaluate f on the instance.
- * Uses the eval_improved functions using the Emerson_Lei algorithm and also returns the number of iterations.
+ * Given a Formula f and a transition system instance (e.g. an LTL), evaluate f on the instance.
+ * Uses the eval_improved functions using the Emerson_Lei algorithm and also returns the statistics.
  */
-pub fn execute_improved(f: Formula, instance:Ltl) -> (HashSet<i64>, i64) {
+pub fn execute_improved<T: TransitionSystem + ?Sized>(f: Formula, instance: &T) -> (HashSet<i64>, Statistics) {
+    execute_improved_with_options(f, instance, &Options::default())
+}
+
+/**
+ * Evaluate f on the instance as execute_improved does, with the threads and tracing of options as for execute_with_options
+ */
+pub fn execute_improved_with_options<T: TransitionSystem + ?Sized>(f: Formula, instance: &T, options: &Options) -> (HashSet<i64>, Statistics) {
+    let mut context = Context::new(&f.root_node, options);
+    execute_improved_in(&f, instance, &mut context)
+}
+
+/**
+ * Evaluate f on the instance as execute_improved does, and record the justification of the result: for every fixed point in
+ *   which iteration each state was decided. Every fixed point starts from the empty set or all states, and identical closed
+ *   subformulas are evaluated again, so that every fixed point has calculations of its own.
+ */
+pub fn execute_justified<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T) -> (HashSet<i64>, Justification) {
+    let mut context = Context::new(&f.root_node, &Options::default());
+    context.justification = Some(Justification::default());
+    context.cache.closed = Arc::new(HashMap::new());
+    let (result, _) = execute_improved_in(f, instance, &mut context);
+    (result, context.justification.unwrap_or_default())
+}
+
+/**
+ * Evaluate f on the instance with eval_improved in the given context
+ */
+fn execute_improved_in<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T, context: &mut Context) -> (HashSet<i64>, Statistics) {
     // First we find the open variables, and initialize the variable map as required for the given mu en nu variables:
     let (variables_open_map, variables_nu, variables_mu) = find_open_variables(&f.root_node);
     let mut variables_map = HashMap::new();
-    initialize_variable_map(&instance, &mut variables_map, &variables_nu, &variables_mu);
-    let mut iterations = 0;
+    initialize_variable_map(instance, &mut variables_map, &variables_nu, &variables_mu);
+    let mut statistics = Statistics::default();
     // Then we call the eval_improved function.
-    (eval_improved(f.root_node, &instance, &mut variables_map, &variables_open_map, &mut iterations), iterations)
+    let result = eval_improved(&f.root_node, instance, &mut variables_map, &variables_open_map, &mut statistics, context);
+    statistics.stopped = context.budget.stopped.reason();
+    (result, statistics)
 }
 
 /**
  * Initialize the map variables_map setting the values empty set for the mu variabels and the set of all states from the Ltl instance for the nu variables.
  */
-fn initialize_variable_map(instance:&Ltl, variable_map: &mut HashMap<String,HashSet<i64>>, variables_nu: &HashSet<String>, variables_mu: &HashSet<String>) {
+fn initialize_variable_map<T: TransitionSystem + ?Sized>(instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>, variables_nu: &HashSet<String>, variables_mu: &HashSet<String>) {
     // If we have a nu variable, insert all states from instance into the map.
     for var in variables_nu {
         (*variable_map).insert(var.clone(), instance.get_all_states());
@@ -163,56 +1135,64 @@ fn initialize_variable_map(instance:&Ltl, variable_map: &mut HashMap<String,Hash
 
 /**
  * Given a Node node and an LTL instance evaluate the set of stats in instance satisfing satisfying the formula represented by node.
- * also requires variable_map, found fixed point variabels and their associated value, iterations, to keep track of the number of fixed 
- * point iterations and variables_open_map to find for each variable, their open subvariables of the same fixpoint. So for a mu variable
+ * also requires variable_map, found fixed point variabels and their associated value, statistics and the context as for eval,
+ * and variables_open_map to find for each variable, their open subvariables of the same fixpoint. So for a mu variable
  * only containing mu variables, and for a nu variable only containing nu variables.
  */
-fn eval_improved(node: Node, instance:&Ltl, variable_map: &mut HashMap<String,HashSet<i64>>, variables_open_map: &HashMap<String
-    , HashSet<String>>, iterations: &mut i64) -> HashSet<i64> {
+fn eval_improved<T: TransitionSystem + ?Sized>(node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>, variables_open_map: &HashMap<String
+    , HashSet<String>>, statistics: &mut Statistics, context: &mut Context) -> HashSet<i64> {
+    // A subformula that does not depend on the fixed point around it may already have been evaluated for the current values:
+    let values = context.cache.values_of(node, variable_map);
+    if let Some(result) = context.cache.get(node, &values, variable_map) {
+        statistics.cache_hits += 1;
+        context.justify(node, &result);
+        return result;
+    }
+    let result = eval_node_improved(node, instance, variable_map, variables_open_map, statistics, context);
+    context.insert(node, values, &result);
+    context.justify(node, &result);
+    result
+}
+
+/**
+ * Evaluate node itself for eval_improved, where its subformulas are evaluated with eval_improved.
+ */
+fn eval_node_improved<T: TransitionSystem + ?Sized>(node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>, variables_open_map: &HashMap<String
+    , HashSet<String>>, statistics: &mut Statistics, context: &mut Context) -> HashSet<i64> {
     match node {
         Node::Variable(string) => {
             // The first case, we need to return the value associated with the variable string in the variable_map.
-            // If this value exists, return the associated value:
-            if (*variable_map).contains_key(&string.clone()) {
-                return variable_map.get(&string.clone()).unwrap().clone();
-            } else {
-                // Otherwise we need to insert an empty set into the map, and return this empty set.
-                (*variable_map).insert(string.clone(), HashSet::new());
-                return variable_map.get(&string.clone()).unwrap().clone();
-            }
+            // If this value does not exist, we insert an empty set into the map and return this empty set:
+            variable_map.entry(string.clone()).or_default().clone()
         }
         Node::BinaryExpr { op, lhs, rhs } => {
              // We have a binary expression, that is an expression over 2 variables (conjunction/disjunction/boxmodality/diamondmodality):
-            if op == Operator::Conjunction {
+            if *op == Operator::Conjunction {
                 // We have a conjunction, we return the intersection of the evaluation of the left and right hand side:
-                let eval_lhs = eval_improved(*lhs, instance, variable_map, variables_open_map, iterations);
-                let eval_rhs = eval_improved(*rhs, instance, variable_map, variables_open_map, iterations);
-                return eval_lhs.intersection(&eval_rhs).map(|x| *x).collect::<HashSet<i64>>();
-            } else if op == Operator::Disjunction {
+                let (eval_lhs, eval_rhs) = eval_operands(node, instance, variable_map, statistics, context, |node, instance, variable_map, statistics, context| eval_improved(node, instance, variable_map, variables_open_map, statistics, context));
+                eval_lhs.intersection(&eval_rhs).copied().collect::<HashSet<i64>>()
+            } else if *op == Operator::Disjunction {
                 // We have a disjunction, we return the union of the evaluation of the left and right hand side:
-                let eval_lhs = eval_improved(*lhs, instance, variable_map, variables_open_map, iterations);
-                let eval_rhs = eval_improved(*rhs, instance, variable_map, variables_open_map, iterations);
-                return eval_lhs.union(&eval_rhs).map(|x| *x).collect::<HashSet<i64>>();
-            } else if op == Operator::DiamondModality {
+                let (eval_lhs, eval_rhs) = eval_operands(node, instance, variable_map, statistics, context, |node, instance, variable_map, statistics, context| eval_improved(node, instance, variable_map, variables_open_map, statistics, context));
+                eval_lhs.union(&eval_rhs).copied().collect::<HashSet<i64>>()
+            } else if *op == Operator::DiamondModality {
                 // We have a diamondmodality, we get the action label and call the get_diamond_modality function 
                 // on the LTL instance to get the diamond modality.
-                let node = *lhs;
-                match node {
+                match &**lhs {
                     Node::Action(string) => {
-                        let states_rhs: HashSet<i64> = eval_improved(*rhs, instance, variable_map, variables_open_map, iterations);
-                        return instance.get_diamond_modality(string, states_rhs)
+                        let states_rhs: HashSet<i64> = eval_improved(rhs, instance, variable_map, variables_open_map, statistics, context);
+                        get_modality(op, string, states_rhs, instance, context.threads)
                     }
                     Node::Variable(_) | Node::UnaryExpr { op: _ } | Node::BinaryExpr { op: _, lhs: _, rhs: _ } | 
                     Node::FixPointExpr { op: _, variable: _, rhs: _, surrounding_binder: _ } => unreachable!(),
                 }
-            } else if op == Operator::BoxModality {
+            } else if *op == Operator::BoxModality {
                 // We have a boxmodality, we get the action label and call the get_box_modality function 
                 // on the LTL instance to get the diamond modality.
-                let node = *lhs;
-                match node {
+                match &**lhs {
                     Node::Action(string) => {
-                        let states_rhs: HashSet<i64> = eval_improved(*rhs, instance, variable_map, variables_open_map, iterations);
-                        return instance.get_box_modality(string, states_rhs)
+                        let states_rhs: HashSet<i64> = eval_improved(rhs, instance, variable_map, variables_open_map, statistics, context);
+                        get_modality(op, string, states_rhs, instance, context.threads)
                     }
                     Node::Variable(_) | Node::UnaryExpr { op: _ } | Node::BinaryExpr { op: _, lhs: _, rhs: _ } | 
                     Node::FixPointExpr { op: _, variable: _, rhs: _, surrounding_binder: _ } => unreachable!(),
@@ -223,36 +1203,39 @@ fn eval_improved(node: Node, instance:&Ltl, variable_map: &mut HashMap<String,Ha
         }
         Node::UnaryExpr { op } => {
             // We have a unary expression, that is either a simple true or a simple false statement.
-            if op == Operator::SimpleTrue {
+            if *op == Operator::SimpleTrue {
                 // If we have a simple true, we simple return all states from instance using instance.get_all_states().
-                let all_states = instance.get_all_states(); 
-                return all_states;
-            } else if op == Operator::SimpleFalse {
+                instance.get_all_states()
+            } else if *op == Operator::SimpleFalse {
                 // We have a simple false, we simply return an empty set.
-                return HashSet::new();
+                HashSet::new()
             } else {
                 panic!("This should not reach any statement except true or false");
             }
         }
-        Node::FixPointExpr { op, variable, rhs, surrounding_binder } => {
+        Node::FixPointExpr { op, variable, rhs: _, surrounding_binder } => {
             // We have a fixed point expression, we first check wheter we have a least fixed point or greatest fixed point.
             // Then in the case of a greatest fixed point, and the surrounding binder of the current fixed point is a least
             // fixed point, we reset variable_map for all variables in the variables_open_map[variable]
             // to the set of all states in the instance.
-            if op == Operator::GreatestFixpoint {
-                if surrounding_binder == Operator::LeastFixpoint {
-                    if let Some(value) = variables_open_map.get(&variable) {
+            if *op == Operator::GreatestFixpoint {
+                if *surrounding_binder == Operator::LeastFixpoint {
+                    if let Some(value) = variables_open_map.get(variable) {
                         for var in value {
                             (*variable_map).insert(var.clone(), instance.get_all_states());
                         }
                     }
                 }
-            } else if op == Operator::LeastFixpoint {
+                // A justification counts the iterations from the first approximant, so it always starts from all states
+                if context.justification.is_some() {
+                    (*variable_map).insert(variable.clone(), instance.get_all_states());
+                }
+            } else if *op == Operator::LeastFixpoint {
             // in the case of a least fixed point, and the surrounding binder of the current fixed point is a greatest
             // fixed point, we reset variable_map for all variables in the variables_open_map[variable]
             // to the empty set.
-                if surrounding_binder == Operator::GreatestFixpoint {
-                    if let Some(value) = variables_open_map.get(&variable) {
+                if *surrounding_binder == Operator::GreatestFixpoint {
+                    if let Some(value) = variables_open_map.get(variable) {
                         for var in value {
                             (*variable_map).insert(var.clone(), HashSet::new());
                         }
@@ -264,7 +1247,7 @@ fn eval_improved(node: Node, instance:&Ltl, variable_map: &mut HashMap<String,Ha
                 panic!("This should not happen");
             }
             // We call the calculate_fixpoint_improved function to calculate the fixed point.
-            return calculate_fixpoint_improved(variable, *rhs, instance, variable_map, variables_open_map, iterations)
+            calculate_fixpoint_improved(node, instance, variable_map, variables_open_map, statistics, context)
         }
         Node::Action(_) => {
             unreachable!("Should not happen");
@@ -273,26 +1256,65 @@ fn eval_improved(node: Node, instance:&Ltl, variable_map: &mut HashMap<String,Ha
 }
 
 /**
- * Given a variable string, a Node g, an LTL instance, a variable_map, variables_open_map and an iterations variable,
+ * Given a fixed point Node node binding string in its body g, an LTL instance, a variable_map, variables_open_map, statistics and a context,
  * calculate the fixed point of g for variable string and return the resulting set.
+ * If the budget of the context runs out, return the last approximant instead.
+ * If the context keeps a justification, the calculation is added to it.
  */
-fn calculate_fixpoint_improved(string: String, g: Node, instance:&Ltl, variable_map: &mut HashMap<String,HashSet<i64>>, 
-    variables_open_map: &HashMap<String, HashSet<String>>, iterations: &mut i64) -> HashSet<i64> {
-    // Set x' to something that is both not the full set and the empty set, to make sure we do not quit immediately:
-    let mut x_prime: HashSet<i64> = HashSet::from([1]); 
+fn calculate_fixpoint_improved<T: TransitionSystem + ?Sized>(node: &Node, instance: &T, variable_map: &mut HashMap<String,HashSet<i64>>, 
+    variables_open_map: &HashMap<String, HashSet<String>>, statistics: &mut Statistics, context: &mut Context) -> HashSet<i64> {
+    let Node::FixPointExpr { op, variable: string, rhs: g, surrounding_binder: _ } = node else { unreachable!() };
+    // There is no x' yet, to make sure we do at least one iteration, also when the fixed point starts from a given value:
+    let mut x_prime: Option<HashSet<i64>> = None;
     // Retrieve the a value from the map:
     let mut a = variable_map.get(&string.clone()).unwrap().clone();
-    // Until x' is equal to a, we keep calculating the fixed point using the eval function and insert the result into the map.
-    while x_prime != a {
-        x_prime = a.clone();
-        let temp = eval_improved(g.clone(), instance, variable_map, variables_open_map, iterations);
+    // The first iteration evaluates g in full, the next iterations only re-examine the states that may change with a:
+    let mut worklist = Worklist::new(string);
+    if context.justification.is_some() {
+        worklist.calculation = Some(Calculation::new(node, &context.path, variable_map));
+    }
+    let mut previous: Option<HashSet<i64>> = None;
+    context.path.push(string.clone());
+    let mut iteration: i64 = 0;
+    // Until x' is equal to a, we keep calculating the fixed point using the worklist and insert the result into the map.
+    while x_prime.as_ref() != Some(&a) {
+        // Stop before the iteration if the budget ran out, unless nothing was calculated yet:
+        if !context.budget.start_iteration() {
+            if iteration > 0 {
+                context.stop(statistics, op, iteration, &a);
+            }
+            break;
+        }
+        x_prime = Some(a.clone());
+        worklist.iteration = iteration + 1;
+        let temp = match previous {
+            None => worklist.init(g, instance, variable_map, variables_open_map, statistics, context),
+            Some(previous) => {
+                worklist.changed = previous.symmetric_difference(&a).cloned().collect();
+                worklist.update(g, instance, variable_map, variables_open_map, statistics, context);
+                worklist.values[&(&**g as *const Node)].clone()
+            }
+        };
+        // If it ran out during the iteration, g was evaluated with approximants of the fixed points in it, so we keep the last one:
+        if context.budget.is_stopped() {
+            context.stop(statistics, op, iteration, &a);
+            break;
+        }
+        previous = x_prime.clone();
         (*variable_map).insert(string.clone(), temp);
         a = variable_map.get(&string.clone()).unwrap().clone();
         // Each iteration of the while loop, we increment iterations by 1:
-        *iterations += 1;
+        statistics.iterations += 1;
+        iteration += 1;
+        context.record(statistics, iteration, &a);
+    }
+    context.path.pop();
+    if let (Some(mut calculation), Some(justification)) = (worklist.calculation.take(), context.justification.as_mut()) {
+        calculation.result = a.clone();
+        justification.calculations.entry(address(node)).or_default().push(calculation);
     }
     // Return the value at variable_map[variable].
-    return variable_map.get(&string.clone()).unwrap().clone();
+    variable_map.get(&string.clone()).unwrap().clone()
 }
 
 /**
@@ -316,7 +1338,7 @@ fn find_open_variables(node: &Node) -> (HashMap<String, HashSet<String>>, HashSe
     // We create the set of open variables for each node by taking the difference of variables_sub_found_map, that is all found variables and all visited variables (variables_sub_map).
     for (key, value) in variables_sub_found_map {
         let temp_set = variables_sub_map.get(&key).unwrap().clone();
-        if (value).difference(&temp_set).map(|x| x.to_string()).collect::<HashSet<String>>().len() > 0 {
+        if !(value).difference(&temp_set).map(|x| x.to_string()).collect::<HashSet<String>>().is_empty() {
             variables_open_set.insert(key);
         }
     }
@@ -379,91 +1401,62 @@ fn find_variables(node: &Node, variables_mu: &mut HashSet<String>, variables_nu:
 }
 
 /**
- * Method to find the nesting depth, alteration depth and dependent alteration depth of a formula given by Node node. Uses nesting_depth, alteration_depth and dependent_alteration_depth
- * to keep track of the current values. And returns a tupple with these 3 values. Variables_map is used to keep track of the variables visited from that variable.
+ * Method to find the nesting depth, alteration depth and dependent alteration depth of a formula given by Node node. Uses nesting_depth and alteration_depth
+ * to keep track of the current values. And returns a tupple with these 3 values. Binders maps the variables of the surrounding fixed points to their operator
+ * and dependent alteration depth.
  */
-fn find_formula_depths(node: &Node, nesting_depth: i64, alteration_depth: i64, dependent_alteration_depth: i64, variables_map: &mut HashMap<String, HashSet<String>>) -> (i64, i64, i64) {
+fn find_formula_depths(node: &Node, nesting_depth: i64, alteration_depth: i64, binders: &HashMap<String, (Operator, i64)>) -> (i64, i64, i64) {
     match node {
         Node::Variable(_) => {
             // For a variable the value is equal to the values when we entered this node.
-            (nesting_depth, alteration_depth, dependent_alteration_depth)
+            (nesting_depth, alteration_depth, 0)
         }
         Node::BinaryExpr { op: _, lhs, rhs } => {
             // In the case of a binary expression the value of the nesting depth, alteration depth and dependent alteration depth is equal to the maximum of the values of either side.
-            let (nesting_depth_lhs, alteration_depth_lhs, dependent_alteration_depth_lhs) = find_formula_depths(lhs, nesting_depth, alteration_depth, dependent_alteration_depth, variables_map);
-            let (nesting_depth_rhs, alteration_depth_rhs, dependent_alteration_depth_rhs) = find_formula_depths(rhs, nesting_depth, alteration_depth, dependent_alteration_depth, variables_map);
+            let (nesting_depth_lhs, alteration_depth_lhs, dependent_alteration_depth_lhs) = find_formula_depths(lhs, nesting_depth, alteration_depth, binders);
+            let (nesting_depth_rhs, alteration_depth_rhs, dependent_alteration_depth_rhs) = find_formula_depths(rhs, nesting_depth, alteration_depth, binders);
             let nesting_depth = max(nesting_depth_lhs, nesting_depth_rhs);
             let alteration_depth = max(alteration_depth_lhs, alteration_depth_rhs);
             let dependent_alteration_depth = max(dependent_alteration_depth_lhs, dependent_alteration_depth_rhs);
             (nesting_depth, alteration_depth, dependent_alteration_depth)
         }
         Node::FixPointExpr { op, variable, rhs, surrounding_binder } => {
-            // For a fixed point expression, we first need to find the nesting depth, alteration depth and dependent alteration depths of the right hand side.
-            let (nesting_depth_rhs, alteration_depth_rhs, dependent_alteration_depth_rhs) = find_formula_depths(rhs, nesting_depth, alteration_depth, dependent_alteration_depth, variables_map);
+            // The dependent alteration depth of a fixed point is one more than the largest one of the fixed points of the other type whose variables it uses.
+            // So a closed fixed point, which is calculated only once, starts again at 1.
+            let dependent_alteration_depth = 1 + find_free_variables(node).iter()
+                .filter_map(|free| binders.get(free))
+                .filter(|(binder, _)| binder != op)
+                .map(|(_, depth)| *depth)
+                .max()
+                .unwrap_or(0);
+            let mut binders = binders.clone();
+            binders.insert(variable.clone(), (op.clone(), dependent_alteration_depth));
+            // Then we need to find the nesting depth, alteration depth and dependent alteration depths of the right hand side.
+            let (nesting_depth_rhs, alteration_depth_rhs, dependent_alteration_depth_rhs) = find_formula_depths(rhs, nesting_depth, alteration_depth, &binders);
+            let dependent_alteration_depth = max(dependent_alteration_depth, dependent_alteration_depth_rhs);
             // If the surrounding binder is the same as the current operator, we simply increment the nesting depth by 1.
             if surrounding_binder == op {
-                (nesting_depth_rhs + 1, alteration_depth_rhs, dependent_alteration_depth_rhs)
+                (nesting_depth_rhs + 1, alteration_depth_rhs, dependent_alteration_depth)
             } else {
-                // If the surrounding binder is not the same as the current operator, we need to check whether the variable is in the variables_map.
-                // If the variable is in the variables map we increment all 3 of nesting depth, alteration depth and dependent alteration depth.
-                if ((*variables_map).get(&variable.clone()).unwrap()).contains(&variable.clone()) {
-                    (nesting_depth_rhs + 1, alteration_depth_rhs + 1, dependent_alteration_depth_rhs + 1)
-                } else {
-                    // Otherwise only increment the nesting depth and alteration depth.
-                    (nesting_depth_rhs + 1, alteration_depth_rhs + 1, dependent_alteration_depth_rhs)
-                }
-            } 
+                // Otherwise we increment both the nesting depth and alteration depth.
+                (nesting_depth_rhs + 1, alteration_depth_rhs + 1, dependent_alteration_depth)
+            }
         }
         Node::UnaryExpr { op: _ } => {
             // For a unary expression the value is equal to the values when we entered this node.
-            (nesting_depth, alteration_depth, dependent_alteration_depth)
+            (nesting_depth, alteration_depth, 0)
         }
         Node::Action(_) => {
             // For an action the value is equal to the values when we entered this node.
-            (nesting_depth, alteration_depth, dependent_alteration_depth)
-        }
-    }
-}
-
-/**
- * Method to find all variables we visit from a given node. Uses variables_map to keep track of the variables visited from that variable.
- * Uses visited_variables to keep track of the variables visited up to a variable (so we can deal with binary expressions).
- */
-fn find_visited_variables(node: &Node, variables_map: &mut HashMap<String, HashSet<String>>, visited_variables: &mut HashSet<String>) {
-    match node {
-        Node::Variable(variable) => {
-            // For a variable, we add this to all values in the visisted_variables set.
-            for var in & *visited_variables {
-                let var_set = variables_map.get_mut(var).unwrap();
-                (*var_set).insert(variable.clone());
-            }
-        }
-        Node::BinaryExpr { op: _, lhs, rhs } => {
-            // For a binary expression, we recursively call the function on both sides with the visited_variables cloned so we have no overlap here.
-            find_visited_variables(lhs, variables_map, &mut visited_variables.clone());
-            find_visited_variables(rhs, variables_map, &mut visited_variables.clone());
-        }
-        Node::FixPointExpr { op: _, variable, rhs, surrounding_binder: _ } => {
-            // For a fixed point expression, we recursively call the function for the rhs, insert an empty hashset for the current value 
-            // and add the variable to the visited_variables set.
-            (*variables_map).insert(variable.clone(), HashSet::new());
-            (*visited_variables).insert(variable.clone());
-            find_visited_variables(rhs, variables_map, visited_variables);
-        }
-        // For the unary expression or action we do nothing.
-        Node::UnaryExpr { op: _} |  Node::Action(_) => {
+            (nesting_depth, alteration_depth, 0)
         }
     }
 }
 
 /**
  * Method to find the nesting depth, alteration depth and dependent alteration depth of a formula given by Node node. 
- * Returns these 3 values in a tupple.
+ * Returns these 3 values in a tupple. A formula has no alternating fixed points if its dependent alteration depth is at most 1.
  */
 pub fn find_formula_statistics(node: &Node) -> (i64, i64, i64) {
-    // initialize some variables and call the find_visited_variables and find_formula_depths functions.
-    let mut variables_map: HashMap<String, HashSet<String>> = HashMap::new();
-    let mut visited_variables: HashSet<String> = HashSet::new();
-    find_visited_variables(node, &mut variables_map, &mut visited_variables);
-    find_formula_depths(node, 0, 0, 0, &mut variables_map)
+    find_formula_depths(node, 0, 0, &HashMap::new())
 }
\ No newline at end of file
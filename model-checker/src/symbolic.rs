@@ -0,0 +1,192 @@
+use crate::bdd::{Bdd, BddManager};
+use crate::types::formula::{Formula, Node, Operator};
+use crate::types::transition_system::TransitionSystem;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+
+// Specify custom type `SymbolicLts`
+// an LTS as Bdds: the k-th state (in sorted order) is k in binary, and so is the k-th label. The transition relation relates
+// a label l and states s and s' and has a Bdd of its own for every label. Label bits come first in the variable order, then
+// the bits of s and s' alternate from the most significant bit, so s' can be renamed to s and back without reordering.
+
+pub struct SymbolicLts {
+    pub manager: BddManager,
+    states: Vec<i64>,
+    state_bits: usize,
+    label_bits: usize,
+    valid: Bdd,
+    relations: HashMap<String, Bdd>,
+    next_states: Bdd,
+}
+
+impl SymbolicLts {
+    /**
+     * Encode an LTS instance
+     */
+    pub fn new<T: TransitionSystem + ?Sized>(instance: &T) -> Self {
+        let mut states: Vec<i64> = instance.states();
+        states.sort();
+        let mut labels: Vec<String> = instance.labels().into_iter().collect();
+        labels.sort();
+        let bits = |count: usize| (usize::BITS - count.saturating_sub(1).leading_zeros()).max(1) as usize;
+        let (state_bits, label_bits) = (bits(states.len()), bits(labels.len()));
+        let mut symbolic = SymbolicLts {
+            manager: BddManager::new(),
+            states,
+            state_bits,
+            label_bits,
+            valid: Bdd::FALSE,
+            relations: HashMap::new(),
+            next_states: Bdd::TRUE,
+        };
+
+        let positions: HashMap<i64, usize> = symbolic.states.iter().enumerate().map(|(position, state)| (*state, position)).collect();
+        let current: Vec<usize> = (0..state_bits).map(|bit| symbolic.current(bit)).collect();
+        let encoded: Vec<Vec<bool>> = (0..positions.len()).map(|position| binary(position, state_bits)).collect();
+        symbolic.valid = symbolic.manager.assignments(&current, encoded);
+
+        // The relation is built from its sorted assignments (l, s_0, s'_0, s_1, s'_1, ...)
+        let variables: Vec<usize> = (0..label_bits + 2 * state_bits).collect();
+        let mut assignments: Vec<Vec<bool>> = Vec::new();
+        for (label_index, label) in labels.iter().enumerate() {
+            for state in symbolic.states.iter() {
+                for successor in instance.successors(*state, label) {
+                    let source = binary(positions[state], state_bits);
+                    let target = binary(positions[&successor], state_bits);
+                    let mut assignment = binary(label_index, label_bits);
+                    assignment.extend(source.into_iter().zip(target).flat_map(|(s, t)| [s, t]));
+                    assignments.push(assignment);
+                }
+            }
+        }
+        let transitions = symbolic.manager.assignments(&variables, assignments);
+
+        // The relation of a label is the transition relation with the label bits fixed, and then removed
+        let label_variables: Vec<(usize, bool)> = (0..label_bits).map(|bit| (bit, true)).collect();
+        let label_cube = symbolic.manager.cube(&label_variables);
+        for (label_index, label) in labels.into_iter().enumerate() {
+            let code: Vec<(usize, bool)> = binary(label_index, label_bits).into_iter().enumerate().collect();
+            let code = symbolic.manager.cube(&code);
+            let relation = symbolic.manager.and_exists(transitions, code, label_cube);
+            symbolic.relations.insert(label, relation);
+        }
+        let next: Vec<(usize, bool)> = (0..state_bits).map(|bit| (symbolic.next(bit), true)).collect();
+        symbolic.next_states = symbolic.manager.cube(&next);
+        symbolic
+    }
+
+    fn current(&self, bit: usize) -> usize {
+        self.label_bits + 2 * bit
+    }
+
+    fn next(&self, bit: usize) -> usize {
+        self.label_bits + 2 * bit + 1
+    }
+
+    /**
+     * Get S, all states
+     */
+    pub fn all_states(&self) -> Bdd {
+        self.valid
+    }
+
+    /**
+     * Get the set of states of a Bdd over the current state bits
+     */
+    pub fn decode(&self, f: Bdd) -> HashSet<i64> {
+        self.states.iter().enumerate()
+            .filter(|(position, _)| {
+                let code = binary(*position, self.state_bits);
+                self.manager.evaluate(f, |variable| code[(variable - self.label_bits) / 2])
+            })
+            .map(|(_, state)| *state)
+            .collect()
+    }
+
+    /**
+     * Get [[ <a>f ]] (DiamondModality), the pre-image of f: exists s'. T_a(s, s') && f(s')
+     */
+    pub fn get_diamond_modality(&mut self, label: &str, f: Bdd) -> Bdd {
+        let Some(relation) = self.relations.get(label).copied() else { return Bdd::FALSE };
+        let renaming: HashMap<usize, usize> = (0..self.state_bits).map(|bit| (self.current(bit), self.next(bit))).collect();
+        let target = self.manager.rename(f, &renaming);
+        self.manager.and_exists(relation, target, self.next_states)
+    }
+
+    /**
+     * Get [[ [a]f ]] (BoxModality), the states that are not in the pre-image of S \ f
+     */
+    pub fn get_box_modality(&mut self, label: &str, f: Bdd) -> Bdd {
+        let outside = self.manager.not(f);
+        let can_leave = self.get_diamond_modality(label, outside);
+        let cannot_leave = self.manager.not(can_leave);
+        self.manager.and(self.valid, cannot_leave)
+    }
+}
+
+/**
+ * Get the `bits` least significant bits of value, from the most significant one
+ */
+fn binary(value: usize, bits: usize) -> Vec<bool> {
+    (0..bits).rev().map(|bit| value >> bit & 1 == 1).collect()
+}
+
+/**
+ * Given a Formula f and an LTL instance, calculate the set of states that satisfy f with the naive algorithm on Bdds.
+ *   Also returns the number of fixed point iterations and the number of Bdd nodes that were made.
+ */
+pub fn execute_symbolic<T: TransitionSystem + ?Sized>(f: Formula, instance: &T) -> (HashSet<i64>, i64, i64) {
+    let mut symbolic = SymbolicLts::new(instance);
+    let mut variable_map: HashMap<String, Bdd> = HashMap::new();
+    let mut iterations: i64 = 0;
+    let result = eval_symbolic(&f.root_node, &mut symbolic, &mut variable_map, &mut iterations);
+    (symbolic.decode(result), iterations, symbolic.manager.node_count() as i64)
+}
+
+/**
+ * Evaluate the Bdd of the states satisfying node, as eval does with sets. Variables that are not bound are the empty set.
+ */
+pub fn eval_symbolic(node: &Node, symbolic: &mut SymbolicLts, variable_map: &mut HashMap<String, Bdd>, iterations: &mut i64) -> Bdd {
+    match node {
+        Node::Variable(variable) => *variable_map.get(variable).unwrap_or(&Bdd::FALSE),
+        Node::UnaryExpr { op: Operator::SimpleTrue } => symbolic.all_states(),
+        Node::UnaryExpr { op: _ } => Bdd::FALSE,
+        Node::BinaryExpr { op, lhs, rhs } => match (op, &**lhs) {
+            (Operator::DiamondModality, Node::Action(label)) => {
+                let states_rhs = eval_symbolic(rhs, symbolic, variable_map, iterations);
+                symbolic.get_diamond_modality(label, states_rhs)
+            }
+            (Operator::BoxModality, Node::Action(label)) => {
+                let states_rhs = eval_symbolic(rhs, symbolic, variable_map, iterations);
+                symbolic.get_box_modality(label, states_rhs)
+            }
+            (Operator::Conjunction, _) => {
+                let states_lhs = eval_symbolic(lhs, symbolic, variable_map, iterations);
+                let states_rhs = eval_symbolic(rhs, symbolic, variable_map, iterations);
+                symbolic.manager.and(states_lhs, states_rhs)
+            }
+            (Operator::Disjunction, _) => {
+                let states_lhs = eval_symbolic(lhs, symbolic, variable_map, iterations);
+                let states_rhs = eval_symbolic(rhs, symbolic, variable_map, iterations);
+                symbolic.manager.or(states_lhs, states_rhs)
+            }
+            _ => unreachable!(),
+        },
+        Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } => {
+            // Bdds are canonical, so the approximants are stable as soon as two consecutive ones are the same node
+            let mut approximant = if *op == Operator::GreatestFixpoint { symbolic.all_states() } else { Bdd::FALSE };
+            loop {
+                variable_map.insert(variable.clone(), approximant);
+                let next = eval_symbolic(rhs, symbolic, variable_map, iterations);
+                *iterations += 1;
+                if next == approximant {
+                    return approximant;
+                }
+                approximant = next;
+            }
+        }
+        Node::Action(_) => unreachable!("Should not happen"),
+    }
+}
@@ -1,4 +1,6 @@
 // module declaration
 
 pub mod formula;
-pub mod ltl;
\ No newline at end of file
+pub mod ltl;
+pub mod label_pattern;
+pub mod transition_system;
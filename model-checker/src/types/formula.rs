@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Operator {
     SimpleFalse, // f = false
@@ -358,6 +360,72 @@ pub fn print_ast(node: &Node, indent: usize) -> String {
     output
 }
 
+/**
+ * Find all actions that occur in a modality of the formula represented by node
+ */
+pub fn find_actions(node: &Node) -> HashSet<String> {
+    let mut actions: HashSet<String> = HashSet::new();
+    match node {
+        Node::Action(action) => {
+            actions.insert(action.clone());
+        }
+        Node::BinaryExpr { op: _, lhs, rhs } => {
+            actions.extend(find_actions(lhs));
+            actions.extend(find_actions(rhs));
+        }
+        Node::FixPointExpr { op: _, variable: _, rhs, surrounding_binder: _ } => {
+            actions.extend(find_actions(rhs));
+        }
+        Node::Variable(_) | Node::UnaryExpr { op: _ } => {}
+    }
+    actions
+}
+
+/**
+ * Print the formula represented by node in the syntax of .mcf files, e.g. `nu X. ([i]X && <plato>true)`
+ */
+pub fn formula_to_string(node: &Node) -> String {
+    match node {
+        Node::Variable(var) => var.clone(),
+        Node::Action(act) => act.clone(),
+        Node::UnaryExpr { op } => {
+            if *op == Operator::SimpleTrue { String::from("true") } else { String::from("false") }
+        }
+        Node::BinaryExpr { op, lhs, rhs } => {
+            match op {
+                Operator::Conjunction => format!("({} && {})", formula_to_string(lhs), formula_to_string(rhs)),
+                Operator::Disjunction => format!("({} || {})", formula_to_string(lhs), formula_to_string(rhs)),
+                Operator::BoxModality => format!("[{}]{}", formula_to_string(lhs), formula_to_string(rhs)),
+                _ => format!("<{}>{}", formula_to_string(lhs), formula_to_string(rhs)),
+            }
+        }
+        Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } => {
+            let binder = if *op == Operator::LeastFixpoint { "mu" } else { "nu" };
+            format!("{} {}. {}", binder, variable, formula_to_string(rhs))
+        }
+    }
+}
+
+/**
+ * Find the free variables of the formula represented by node, i.e. the variables that are not bound by a fixpoint in node
+ */
+pub fn find_free_variables(node: &Node) -> HashSet<String> {
+    match node {
+        Node::Variable(var) => HashSet::from([var.clone()]),
+        Node::BinaryExpr { op: _, lhs, rhs } => {
+            let mut free = find_free_variables(lhs);
+            free.extend(find_free_variables(rhs));
+            free
+        }
+        Node::FixPointExpr { op: _, variable, rhs, surrounding_binder: _ } => {
+            let mut free = find_free_variables(rhs);
+            free.remove(variable);
+            free
+        }
+        Node::UnaryExpr { op: _ } | Node::Action(_) => HashSet::new(),
+    }
+}
+
 impl Formula {
     pub fn new(input_formula: String, debug: bool) -> Self {
         if debug { println!("Creating new formula") }
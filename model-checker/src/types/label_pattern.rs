@@ -0,0 +1,52 @@
+use regex::Regex;
+
+// Specify custom type `LabelPattern`
+// used to select transition labels when hiding or renaming actions of an `Ltl`
+
+#[derive(Debug, Clone)]
+pub enum LabelPattern {
+    Exact(String), // matches exactly this label, e.g. `lock(p1, f1)`
+    Regex(Regex),  // matches every label that is fully matched by the regex, e.g. `/lock\(.*\)/`
+}
+
+impl LabelPattern {
+    /**
+     * Parse a pattern given on the command line, a pattern surrounded by slashes (e.g. `/free\(.*\)/`) is a regex,
+     * anything else is an exact label.
+     */
+    pub fn new(pattern: &str) -> Self {
+        if pattern.len() >= 2 && pattern.starts_with('/') && pattern.ends_with('/') {
+            // Anchor the regex, so it has to match the whole label
+            let regex = format!("^(?:{})$", &pattern[1..pattern.len()-1]);
+            return LabelPattern::Regex(Regex::new(&regex)
+                .unwrap_or_else(|e| panic!("Pattern '{}' is not a valid regex: {}", pattern, e)));
+        }
+        LabelPattern::Exact(pattern.to_string())
+    }
+
+    /**
+     * Check whether `label` is matched by the pattern
+     */
+    pub fn matches(&self, label: &str) -> bool {
+        match self {
+            LabelPattern::Exact(exact) => exact == label,
+            LabelPattern::Regex(regex) => regex.is_match(label),
+        }
+    }
+
+    /**
+     * If `label` is matched by the pattern, return what it is renamed to.
+     *   For a regex, `replacement` may refer to capture groups, e.g. `/(lock|free)\(.*\)/` with `$1` renames
+     *   `lock(p1, f1)` to `lock`.
+     */
+    pub fn rename(&self, label: &str, replacement: &str) -> Option<String> {
+        match self {
+            LabelPattern::Exact(exact) => {
+                if exact == label { Some(replacement.to_string()) } else { None }
+            }
+            LabelPattern::Regex(regex) => {
+                if regex.is_match(label) { Some(regex.replace(label, replacement).into_owned()) } else { None }
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 use std::collections::HashMap;
 
+use super::label_pattern::LabelPattern;
+use super::transition_system::TransitionSystem;
+
 // Specify custom type `Ltl`
 // follows `https://www.mcrl2.org/web/user_manual/tools/lts.html`
 
@@ -52,35 +55,43 @@ impl Ltl{
             println!("adding line ({},{},{})", start_state, label, end_state);
         }
         
-        let mut transition_dict = self.transitions.clone();
-        if transition_dict.contains_key(&start_state) {
-            // if start_state already in transition dict, add the given transition to its hashmap
-            let mut state_map: HashMap< String, HashSet<i64>> = transition_dict.get(&start_state)
-                .expect("Won't happen, see contains_key() above").clone();
-            if state_map.contains_key(&String::from(label)) {
-                // State map already contains this transition, 
-                let mut end_states: HashSet<i64> = state_map.get(&String::from(label))
-                .expect("Won't happen, see contains_key() above").clone();
-                if !end_states.contains(&end_state) {
-                    // this state is not yet in end_states, add it to the vector
-                    end_states.insert(end_state);
-                }
-                state_map.insert(String::from(label), end_states);
-                
-            } else {
-                state_map.insert(String::from(label), HashSet::from([end_state]));
-            }
-            transition_dict.insert(start_state, state_map);
+        // Insert the end state into the set of `label` successors of start_state, creating the maps on the way if needed.
+        // NOTE: we mutate in place, cloning the full transition map per edge made building large LTSs quadratic.
+        self.transitions
+            .entry(start_state)
+            .or_default()
+            .entry(String::from(label))
+            .or_default()
+            .insert(end_state);
+
+        return self
+    }
 
-        } else {
-            // else initialize the transition ==> insert (start_state, HashMap({label, [end_state]}))
-            let mut state_map: HashMap< String, HashSet<i64>> = HashMap::new();
-            state_map.insert(String::from(label),  HashSet::from([end_state]));
-            transition_dict.insert(start_state, state_map);
+    /**
+     * remove an edge, returns whether it was there
+     */
+    // Only the incremental API of the library edits a loaded LTS, the binary does not
+    #[allow(dead_code)]
+    pub fn remove_transition(&mut self, start_state: i64, label: &str, end_state: i64, debug: bool) -> bool {
+        if start_state < 0 || start_state >= self.nr_of_states {
+            panic!("start_state '{}' not correct", start_state)
+        }
+        if end_state < 0 || end_state >= self.nr_of_states {
+            panic!("end_state '{}' not correct", end_state)
         }
 
-        self.transitions = transition_dict;
-        return self
+        if debug {
+            println!("removing line ({},{},{})", start_state, label, end_state);
+        }
+
+        // A label without transitions left is removed as well, so the state looks as if it never had any
+        let Some(state_map) = self.transitions.get_mut(&start_state) else { return false };
+        let Some(targets) = state_map.get_mut(label) else { return false };
+        let removed = targets.remove(&end_state);
+        if targets.is_empty() {
+            state_map.remove(label);
+        }
+        removed
     }
 
     /**
@@ -142,4 +153,179 @@ impl Ltl{
         return output;
     }
 
+    /**
+     * Get Act, all labels that occur on some transition
+     */
+    pub fn get_labels(&self) -> HashSet<String> {
+        self.transitions.values()
+            .flat_map(|state_map| state_map.keys().cloned())
+            .collect()
+    }
+
+    /**
+     * Get the strongly connected components of the LTS, optionally restricted to the transitions labelled `label`.
+     *   Returns for every state the index of its component, together with the number of components.
+     *   Components are numbered in the order Tarjan's algorithm closes them, so every transition between two different
+     *   components goes from a higher to a lower index (i.e. the numbering is a reverse topological order).
+     */
+    pub fn get_strongly_connected_components(&self, label: Option<&str>) -> (Vec<i64>, i64) {
+        let n = self.nr_of_states as usize;
+        let successors = |state: usize| -> Vec<usize> {
+            let mut out: Vec<usize> = Vec::new();
+            if let Some(state_map) = self.transitions.get(&(state as i64)) {
+                for (l, targets) in state_map {
+                    if label.is_none_or(|a| a == l) {
+                        out.extend(targets.iter().map(|t| *t as usize));
+                    }
+                }
+            }
+            out
+        };
+
+        // Iterative version of Tarjan's algorithm, so deep LTSs do not overflow the call stack
+        let mut index: Vec<Option<usize>> = vec![None; n];
+        let mut lowlink: Vec<usize> = vec![0; n];
+        let mut on_stack: Vec<bool> = vec![false; n];
+        let mut component: Vec<i64> = vec![-1; n];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut next_index = 0;
+        let mut nr_of_components: i64 = 0;
+
+        for root in 0..n {
+            if index[root].is_some() {
+                continue;
+            }
+            // Each call frame holds the state and the successors that still have to be visited
+            let mut call_stack: Vec<(usize, Vec<usize>)> = vec![(root, successors(root))];
+            index[root] = Some(next_index);
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((state, todo)) = call_stack.last_mut() {
+                let state = *state;
+                if let Some(next) = todo.pop() {
+                    match index[next] {
+                        None => {
+                            index[next] = Some(next_index);
+                            lowlink[next] = next_index;
+                            next_index += 1;
+                            stack.push(next);
+                            on_stack[next] = true;
+                            call_stack.push((next, successors(next)));
+                        }
+                        Some(i) if on_stack[next] => {
+                            lowlink[state] = lowlink[state].min(i);
+                        }
+                        Some(_) => {}
+                    }
+                } else {
+                    call_stack.pop();
+                    if let Some((parent, _)) = call_stack.last() {
+                        lowlink[*parent] = lowlink[*parent].min(lowlink[state]);
+                    }
+                    // state is the root of a component, pop it from the stack
+                    if Some(lowlink[state]) == index[state] {
+                        while let Some(member) = stack.pop() {
+                            on_stack[member] = false;
+                            component[member] = nr_of_components;
+                            if member == state {
+                                break;
+                            }
+                        }
+                        nr_of_components += 1;
+                    }
+                }
+            }
+        }
+
+        (component, nr_of_components)
+    }
+
+    /**
+     * Build a copy of the LTS in which every label l is replaced by rename(l)
+     */
+    pub fn map_labels<F: Fn(&str) -> String>(&self, rename: F) -> Ltl {
+        let mut ltl = Ltl::new(self.first_state, 0, self.nr_of_states);
+        for (state, state_map) in &self.transitions {
+            for (label, targets) in state_map {
+                let new_label = rename(label);
+                for target in targets {
+                    ltl.add_transition(*state, &new_label, *target, false);
+                }
+            }
+        }
+        ltl
+    }
+
+    /**
+     * Hide all labels matched by one of `labels`, i.e. rename them to the internal action
+     */
+    pub fn hide(&self, labels: &[LabelPattern], internal_action: &str) -> Ltl {
+        self.map_labels(|label| {
+            if labels.iter().any(|pattern| pattern.matches(label)) {
+                internal_action.to_string()
+            } else {
+                label.to_string()
+            }
+        })
+    }
+
+    /**
+     * Rename labels, every label is renamed by the first rule (pattern, replacement) whose pattern matches it.
+     *   Labels that are not matched by any rule keep their name, labels that are renamed to the same name are merged.
+     */
+    pub fn rename(&self, renaming: &[(LabelPattern, String)]) -> Ltl {
+        self.map_labels(|label| {
+            renaming.iter()
+                .find_map(|(pattern, replacement)| pattern.rename(label, replacement))
+                .unwrap_or_else(|| label.to_string())
+        })
+    }
+
+}
+
+impl TransitionSystem for Ltl {
+    fn states(&self) -> Vec<i64> {
+        (0..self.nr_of_states).collect()
+    }
+
+    fn initial_state(&self) -> i64 {
+        self.first_state
+    }
+
+    fn successors(&self, state: i64, label: &str) -> Vec<i64> {
+        self.transitions.get(&state)
+            .and_then(|state_map| state_map.get(label))
+            .map(|targets| targets.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /**
+     * There is no reverse index, so this scans all transitions
+     */
+    fn predecessors(&self, state: i64, label: &str) -> Vec<i64> {
+        self.transitions.iter()
+            .filter(|(_, state_map)| state_map.get(label).is_some_and(|targets| targets.contains(&state)))
+            .map(|(source, _)| *source)
+            .collect()
+    }
+
+    fn labels(&self) -> HashSet<String> {
+        self.get_labels()
+    }
+
+    // The modalities can be computed with one pass over the transitions, rather than one per state in F
+    fn get_all_states(&self) -> HashSet<i64> {
+        Ltl::get_all_states(self)
+    }
+
+    fn get_diamond_modality(&self, label: String, out_states: HashSet<i64>) -> HashSet<i64> {
+        Ltl::get_diamond_modality(self, label, out_states)
+    }
+
+    fn get_box_modality(&self, label: String, out_states: HashSet<i64>) -> HashSet<i64> {
+        Ltl::get_box_modality(self, label, out_states)
+    }
 }
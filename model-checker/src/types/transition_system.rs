@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+// Specify custom trait `TransitionSystem`
+// everything the solvers need to know about a labelled transition system, so they also run on state spaces
+// that are never stored as an `Ltl`, e.g. products, quotients or models that are generated on the fly.
+// The solvers may read it from several threads at once, hence `Sync`.
+
+pub trait TransitionSystem: Sync {
+    /**
+     * Get S, all states
+     */
+    fn states(&self) -> Vec<i64>;
+
+    /**
+     * Get the initial state
+     */
+    fn initial_state(&self) -> i64;
+
+    /**
+     * Get the states reachable from `state` with one `label` transition
+     */
+    fn successors(&self, state: i64, label: &str) -> Vec<i64>;
+
+    /**
+     * Get the states that reach `state` with one `label` transition
+     */
+    fn predecessors(&self, state: i64, label: &str) -> Vec<i64>;
+
+    /**
+     * Get Act, all labels that occur on some transition
+     */
+    fn labels(&self) -> HashSet<String>;
+
+    /**
+     * Get S as a set
+     */
+    fn get_all_states(&self) -> HashSet<i64> {
+        self.states().into_iter().collect()
+    }
+
+    /**
+     * Get [[ <a>f ]] (DiamondModality),
+     *   Get all states that have some a-transition into a state in set F, i.e. the a-predecessors of F
+     */
+    fn get_diamond_modality(&self, label: String, out_states: HashSet<i64>) -> HashSet<i64> {
+        out_states.iter()
+            .flat_map(|state| self.predecessors(*state, &label))
+            .collect()
+    }
+
+    /**
+     * Get [[ [a]f ]] (BoxModality),
+     *   Get all states that have all a-transition into a state in set F, i.e. the states that are not an a-predecessor of S \ F
+     */
+    fn get_box_modality(&self, label: String, out_states: HashSet<i64>) -> HashSet<i64> {
+        let all_states = self.get_all_states();
+        let outside: HashSet<i64> = all_states.difference(&out_states).cloned().collect();
+        let can_leave = self.get_diamond_modality(label, outside);
+        all_states.difference(&can_leave).cloned().collect()
+    }
+}
@@ -0,0 +1,410 @@
+use crate::solver::{execute_justified, Calculation, Justification};
+use crate::types::formula::{Formula, Node, Operator, formula_to_string};
+use crate::types::ltl::Ltl;
+use crate::types::transition_system::TransitionSystem;
+
+use std::cmp::min;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+
+// A transition (source, label, target)
+pub type Step = (i64, String, i64);
+
+// Specify custom type `Witness`
+// a run from the initial state that shows why it satisfies a formula (a witness) or why it does not (a counterexample):
+// a finite prefix, possibly followed by a cycle that is repeated forever (a lasso)
+
+#[derive(Debug, PartialEq)]
+pub struct Witness {
+    pub prefix: Vec<Step>,
+    pub cycle: Vec<Step>,
+}
+
+// Specify custom type `Evidence`
+// the part of an LTS that proves the initial state satisfies a formula: every diamond obligation is met by one transition,
+// every box obligation by all transitions with its label, and every state is annotated with the subformulas it has to satisfy
+
+#[derive(Debug, PartialEq)]
+pub struct Evidence {
+    pub states: BTreeMap<i64, BTreeSet<String>>,
+    pub transitions: BTreeSet<Step>,
+}
+
+// A fixed point in scope during the walk, with the calculation of it for the values of the variables around it. For a least
+// fixed point in a witness and a greatest fixed point in a counterexample `level` is the number of iterations of the approximant
+// we currently use as its value, for the other fixed points we use the fixed point itself.
+#[derive(Clone)]
+struct Binding<'a> {
+    variable: &'a str,
+    binder: &'a Node,
+    body: &'a Node,
+    index: usize,
+    calculation: &'a Calculation,
+    level: Option<i64>,
+}
+
+// The fixed points in scope, with the calculation and level of each of them
+type Scope = Vec<(*const Node, usize, Option<i64>)>;
+
+// The justification the solver recorded for the result of a formula, which the walks look up instead of evaluating
+// subformulas again, with the calculation of every fixed point that was found for the variables in scope
+struct Walk<'a, T: ?Sized> {
+    instance: &'a T,
+    justification: &'a Justification,
+    ranked: Operator,
+    found: HashMap<(*const Node, Scope), usize>,
+}
+
+impl Witness {
+    /**
+     * Build the sub-LTS of the states and transitions on the run, which starts in `initial_state`.
+     *   States are renumbered in the order the run visits them, so the initial state becomes state 0.
+     *   Also returns the original state of every state of the sub-LTS.
+     */
+    pub fn to_ltl(&self, initial_state: i64) -> (Ltl, Vec<i64>) {
+        let steps: Vec<&Step> = self.prefix.iter().chain(&self.cycle).collect();
+        restrict(initial_state, steps.iter().map(|(_, _, target)| *target), &steps)
+    }
+}
+
+impl Evidence {
+    /**
+     * Build the sub-LTS of the evidence, which starts in `initial_state`.
+     *   States are renumbered in increasing order, except that the initial state becomes state 0.
+     *   Also returns the original state of every state of the sub-LTS.
+     */
+    pub fn to_ltl(&self, initial_state: i64) -> (Ltl, Vec<i64>) {
+        let steps: Vec<&Step> = self.transitions.iter().collect();
+        restrict(initial_state, self.states.keys().cloned(), &steps)
+    }
+}
+
+/**
+ * Build the LTS with the given states, numbered in order after `initial_state` which becomes state 0, and the given transitions
+ * between them. Also returns the original state of every state of the new LTS.
+ */
+fn restrict<I: Iterator<Item = i64>>(initial_state: i64, states: I, transitions: &[&Step]) -> (Ltl, Vec<i64>) {
+    let mut original: Vec<i64> = vec![initial_state];
+    let mut renumbered: HashMap<i64, i64> = HashMap::from([(initial_state, 0)]);
+    for state in states {
+        renumbered.entry(state).or_insert_with(|| {
+            original.push(state);
+            original.len() as i64 - 1
+        });
+    }
+
+    let mut ltl = Ltl::new(0, transitions.len() as i64, original.len() as i64);
+    for (source, label, target) in transitions {
+        ltl.add_transition(renumbered[source], label, renumbered[target], false);
+    }
+    (ltl, original)
+}
+
+/**
+ * Given a Formula f and an LTL instance, find a witness that the initial state satisfies f, or None if it does not.
+ *
+ * We walk the formula from the initial state, choosing a disjunct that holds at a disjunction, a conjunct with a diamond
+ * at a conjunction, and a successor that satisfies the rest of the formula at a diamond. Boxes and true end the walk.
+ * Every time we pass a least fixed point variable X in a state, we continue with the largest approximant of X that does
+ * not contain the state yet as the value of X, so the state has to reach the approximant in a later unfolding of X.
+ * This can only happen finitely often, so a cycle in the walk has to pass a greatest fixed point and is a valid lasso.
+ * The approximants and the successors that decide diamonds are the ones execute_justified recorded while it calculated
+ * the fixed points, so the walk only looks states up.
+ */
+pub fn find_witness<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T) -> Option<Witness> {
+    find_run(f, instance, true)
+}
+
+/**
+ * Given a Formula f and an LTL instance, find a counterexample that shows the initial state does not satisfy f,
+ * or None if it does. It ends in a state that violates f (e.g. a deadlock violating `<a>true`), or is a lasso whose
+ * cycle violates f forever (e.g. a cycle without `plato` steps when plato should inevitably eat).
+ *
+ * This is the dual of `find_witness`: we choose a conjunct that fails, a disjunct with a box and a successor that
+ * violates the rest of the formula at a box, and use the approximants of greatest fixed points, in which a state is
+ * decided when it is removed. They come from the same justification, with the successors that decided boxes.
+ */
+pub fn find_counterexample<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T) -> Option<Witness> {
+    find_run(f, instance, false)
+}
+
+/**
+ * Walk the formula from the initial state to find a witness if `satisfied`, or a counterexample otherwise
+ */
+fn find_run<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T, satisfied: bool) -> Option<Witness> {
+    let mut state = instance.initial_state();
+    let (result, justification) = execute_justified(f, instance);
+    if result.contains(&state) != satisfied {
+        return None;
+    }
+    // The modality we follow, the other one has nothing to show on a single run
+    let (follow, ranked) = if satisfied {
+        (Operator::DiamondModality, Operator::LeastFixpoint)
+    } else {
+        (Operator::BoxModality, Operator::GreatestFixpoint)
+    };
+    let mut walk = Walk { instance, justification: &justification, ranked, found: HashMap::new() };
+
+    let mut steps: Vec<Step> = Vec::new();
+    let mut stack: Vec<Binding> = Vec::new();
+    // The number of steps taken when we unfolded a variable in a state with the same values of the variables in scope
+    let mut visited: HashMap<(i64, *const Node, Scope), usize> = HashMap::new();
+    let mut node: &Node = &f.root_node;
+
+    loop {
+        let variable: &str = match node {
+            // A disjunction in a witness and a conjunction in a counterexample: choose a side that decides it
+            Node::BinaryExpr { op: Operator::Disjunction, lhs, rhs } if satisfied => {
+                node = if walk.holds(lhs, state, &stack) { lhs } else { rhs };
+                continue;
+            }
+            Node::BinaryExpr { op: Operator::Conjunction, lhs, rhs } if !satisfied => {
+                node = if walk.holds(lhs, state, &stack) { rhs } else { lhs };
+                continue;
+            }
+            // Otherwise both sides decide it, follow one that has something to show
+            Node::BinaryExpr { op: Operator::Conjunction | Operator::Disjunction, lhs, rhs } => {
+                match [lhs, rhs].into_iter().find(|side| contains_modality(side, &follow)) {
+                    Some(side) => node = side,
+                    None => break,
+                }
+                continue;
+            }
+            Node::BinaryExpr { op, lhs: _, rhs } if *op == follow => {
+                let (label, target) = walk.successor(node, state, &stack, satisfied);
+                steps.push((state, label.clone(), target));
+                state = target;
+                node = rhs;
+                continue;
+            }
+            Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } => {
+                walk.enter(node, &mut stack);
+                if *op != walk.ranked {
+                    node = rhs;
+                    continue;
+                }
+                variable
+            }
+            Node::Variable(variable) => variable,
+            // true or false, or the other modality
+            _ => break,
+        };
+
+        node = unfold(variable, state, &mut stack);
+        let key = (state, node as *const Node, to_scope(&stack));
+        if let Some(start) = visited.get(&key) {
+            let cycle = steps.split_off(*start);
+            return Some(Witness { prefix: steps, cycle });
+        }
+        visited.insert(key, steps.len());
+    }
+
+    Some(Witness { prefix: steps, cycle: Vec::new() })
+}
+
+/**
+ * Given a Formula f and an LTL instance, find the evidence that the initial state satisfies f, or None if it does not.
+ *
+ * We explore the strategy of `find_witness` from the initial state, but instead of following one side of a conjunction
+ * or one transition of a box we follow all of them. Every state that is reached is annotated with the modalities and
+ * fixed points it satisfies, e.g. `<plato>true`. Subformulas with free variables are annotated as they are.
+ * An obligation inside a least fixed point uses the first approximant that satisfies it, so it is explored only once.
+ */
+pub fn find_evidence<T: TransitionSystem + ?Sized>(f: &Formula, instance: &T) -> Option<Evidence> {
+    let initial_state = instance.initial_state();
+    let (result, justification) = execute_justified(f, instance);
+    if !result.contains(&initial_state) {
+        return None;
+    }
+    let mut walk = Walk { instance, justification: &justification, ranked: Operator::LeastFixpoint, found: HashMap::new() };
+
+    let mut evidence = Evidence { states: BTreeMap::new(), transitions: BTreeSet::new() };
+    let mut annotations: HashMap<*const Node, String> = HashMap::new();
+    let mut annotate = |evidence: &mut Evidence, state: i64, node: &Node| {
+        let annotation = annotations.entry(node).or_insert_with(|| formula_to_string(node)).clone();
+        evidence.states.entry(state).or_default().insert(annotation);
+    };
+    annotate(&mut evidence, initial_state, &f.root_node);
+    let mut visited: HashSet<(i64, *const Node, Scope)> = HashSet::new();
+    let mut todo: Vec<(i64, &Node, Vec<Binding>)> = vec![(initial_state, &f.root_node, Vec::new())];
+
+    while let Some((state, node, mut stack)) = todo.pop() {
+        lower(node, state, &mut stack);
+        if !visited.insert((state, node as *const Node, to_scope(&stack))) {
+            continue;
+        }
+        evidence.states.entry(state).or_default();
+        let variable: &str = match node {
+            Node::BinaryExpr { op: Operator::Disjunction, lhs, rhs } => {
+                let side: &Node = if walk.holds(lhs, state, &stack) { lhs } else { rhs };
+                todo.push((state, side, stack));
+                continue;
+            }
+            Node::BinaryExpr { op: Operator::Conjunction, lhs, rhs } => {
+                todo.push((state, rhs, stack.clone()));
+                todo.push((state, lhs, stack));
+                continue;
+            }
+            Node::BinaryExpr { op, lhs, rhs } => {
+                annotate(&mut evidence, state, node);
+                let label = match &**lhs {
+                    Node::Action(label) => label,
+                    _ => unreachable!("A modality should have an action on its left hand side"),
+                };
+                let targets = if *op == Operator::DiamondModality {
+                    vec![walk.successor(node, state, &stack, true).1]
+                } else {
+                    let mut targets = instance.successors(state, label);
+                    targets.sort();
+                    targets
+                };
+                for target in targets {
+                    evidence.transitions.insert((state, label.clone(), target));
+                    todo.push((target, rhs, stack.clone()));
+                }
+                continue;
+            }
+            Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } => {
+                annotate(&mut evidence, state, node);
+                walk.enter(node, &mut stack);
+                if *op != Operator::LeastFixpoint {
+                    todo.push((state, rhs, stack));
+                    continue;
+                }
+                variable
+            }
+            Node::Variable(variable) => variable,
+            _ => continue,
+        };
+
+        // Unfold the variable like `find_run` does
+        let body = unfold(variable, state, &mut stack);
+        annotate(&mut evidence, state, stack[stack.len() - 1].binder);
+        todo.push((state, body, stack));
+    }
+
+    Some(evidence)
+}
+
+impl<'a, T: TransitionSystem + ?Sized> Walk<'a, T> {
+    /**
+     * Check whether state satisfies node, given the values of the variables in scope
+     */
+    fn holds(&self, node: &Node, state: i64, stack: &[Binding]) -> bool {
+        match stack.last() {
+            Some(binding) => binding.calculation.holds(node, state, binding.level.map(|level| level + 1)),
+            None => self.justification.holds(node, state),
+        }
+    }
+
+    /**
+     * Get the label and the successor of state that decides the modality node if `satisfied`, or that violates it otherwise:
+     *   the one the solver recorded if it did, or else the first one
+     */
+    fn successor<'n>(&self, node: &'n Node, state: i64, stack: &[Binding], satisfied: bool) -> (&'n String, i64) {
+        let (label, rhs) = match node {
+            Node::BinaryExpr { op: _, lhs, rhs } => match &**lhs {
+                Node::Action(label) => (label, rhs),
+                _ => unreachable!("A modality should have an action on its left hand side"),
+            },
+            _ => unreachable!("Only a modality has successors"),
+        };
+        if let Some(target) = stack.last().and_then(|binding| binding.calculation.successor(node, state)) {
+            return (label, target);
+        }
+        let mut targets = self.instance.successors(state, label);
+        targets.sort();
+        let target = targets.into_iter()
+            .find(|target| self.holds(rhs, *target, stack) == satisfied)
+            .expect("Some successor should decide the modality");
+        (label, target)
+    }
+
+    /**
+     * Bind the fixed point node, with its calculation for the values of the variables in scope
+     */
+    fn enter(&mut self, node: &'a Node, stack: &mut Vec<Binding<'a>>) {
+        let Node::FixPointExpr { op, variable, rhs, surrounding_binder: _ } = node else { unreachable!() };
+        let calculations = self.justification.calculations(node);
+        let key = (node as *const Node, to_scope(stack));
+        let index = match self.found.get(&key) {
+            Some(index) => *index,
+            None => {
+                let values: HashMap<&str, HashSet<i64>> = calculations.first().map_or(&[][..], |calculation| calculation.environment()).iter()
+                    .filter_map(|(variable, _)| stack.iter().rev().find(|binding| binding.variable == variable))
+                    .map(|binding| (binding.variable, self.value(binding)))
+                    .collect();
+                let index = calculations.iter()
+                    .position(|calculation| calculation.environment().iter()
+                        .all(|(variable, value)| values.get(variable.as_str()).is_none_or(|current| current == value)))
+                    .expect("The fixed point should have been calculated for the values of the variables in scope");
+                self.found.insert(key, index);
+                index
+            }
+        };
+        // The level of a ranked fixed point is set when its variable is unfolded
+        let level = (*op == self.ranked).then_some(0);
+        stack.push(Binding { variable, binder: node, body: rhs, index, calculation: &calculations[index], level });
+    }
+
+    /**
+     * Get the current value of the variable of binding
+     */
+    fn value(&self, binding: &Binding) -> HashSet<i64> {
+        match binding.level {
+            Some(level) => self.instance.states().into_iter()
+                .filter(|state| binding.calculation.holds(binding.body, *state, Some(level)))
+                .collect(),
+            None => binding.calculation.result().clone(),
+        }
+    }
+}
+
+/**
+ * Unfold variable in state, forgetting the variables bound inside its body, and return the body. A ranked variable continues
+ *   with the last approximant that does not contain the state yet (mu) or still contains it (nu).
+ */
+fn unfold<'a>(variable: &str, state: i64, stack: &mut Vec<Binding<'a>>) -> &'a Node {
+    let index = stack.iter().rposition(|binding| binding.variable == variable)
+        .unwrap_or_else(|| panic!("Variable {} is not bound", variable));
+    stack.truncate(index + 1);
+    let binding = &mut stack[index];
+    if binding.level.is_some() {
+        let rank = binding.calculation.rank(binding.body, state).expect("The fixed point should decide the state");
+        binding.level = Some(rank - 1);
+    }
+    binding.body
+}
+
+/**
+ * Continue with the first approximant of the innermost fixed point in which state satisfies node, if it is ranked
+ */
+fn lower(node: &Node, state: i64, stack: &mut [Binding]) {
+    if let Some(binding) = stack.last_mut() {
+        if let (Some(level), Some(rank)) = (binding.level, binding.calculation.rank(node, state)) {
+            binding.level = Some(min(level, rank - 1));
+        }
+    }
+}
+
+/**
+ * Get the fixed points in scope, with the calculation and level of each of them
+ */
+fn to_scope(stack: &[Binding]) -> Scope {
+    stack.iter().map(|binding| (binding.binder as *const Node, binding.index, binding.level)).collect()
+}
+
+/**
+ * Check whether the formula represented by node contains a modality `modality` (box or diamond)
+ */
+fn contains_modality(node: &Node, modality: &Operator) -> bool {
+    match node {
+        Node::BinaryExpr { op, lhs: _, rhs: _ } if op == modality => true,
+        Node::BinaryExpr { op: _, lhs, rhs } => contains_modality(lhs, modality) || contains_modality(rhs, modality),
+        Node::FixPointExpr { op: _, variable: _, rhs, surrounding_binder: _ } => contains_modality(rhs, modality),
+        _ => false,
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test_alternation_free {
+    use model_checker::alternation_free::execute_alternation_free;
+    use model_checker::bes::execute_gauss;
+    use model_checker::generators::random_lts;
+    use model_checker::solver::find_formula_statistics;
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_same_result_as_gauss() {
+        let formulas = [
+            "true",
+            "<a0>[a1]false",
+            "mu X. (<a0>true || <a1>X)",
+            "nu X. ([a0]X && <a1>true)",
+            "mu X. mu Y. ((<a0>X || <a1>Y) || [a2]false)",
+            "nu X. ([a0]X && mu Y. (<a1>true || <a2>Y))",
+            "mu X. ([a1]X && nu Y. (<a0>Y && <a2>true))",
+            "(nu X. ([a1]X && mu Y. (<a0>true || <a2>Y)) && <a1>true)",
+            "mu X. (<a0>X || Z)",
+        ];
+
+        for seed in 0..5 {
+            let ltl = random_lts(30, 2, 3, seed);
+            for text in formulas {
+                let (expected, _) = execute_gauss(Formula::new(text.to_string(), false), &ltl);
+                let (result, _) = execute_alternation_free(&Formula::new(text.to_string(), false), &ltl)
+                    .expect("The formula should be alternation-free");
+                assert_eq!(result, expected, "{} on seed {}", text, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_alternating_formula() {
+        let ltl = random_lts(10, 2, 2, 0);
+        let f = Formula::new("nu X. mu Y. (<a0>X || <a1>Y)".to_string(), false);
+        assert_eq!(execute_alternation_free(&f, &ltl), None);
+    }
+
+    #[test]
+    fn test_dependent_alteration_depth() {
+        // the closed mu Y is calculated once, so only the second formula has alternating fixed points
+        let (_, _, dependent_alteration_depth) = find_formula_statistics(&Formula::new("nu X. ([i]X && mu Y. (<plato>true || <i>Y))".to_string(), false).root_node);
+        assert_eq!(dependent_alteration_depth, 1);
+        let (_, _, dependent_alteration_depth) = find_formula_statistics(&Formula::new("nu X. ([i]X && mu Y. (<plato>X || <i>Y))".to_string(), false).root_node);
+        assert_eq!(dependent_alteration_depth, 2);
+        assert_eq!(find_formula_statistics(&Formula::new("nu X. mu Y. (<a0>X || <a1>Y)".to_string(), false).root_node), (2, 2, 2));
+    }
+
+    #[test]
+    fn test_linear_number_of_updates() {
+        // a chain 0 -i-> 1 -i-> ... -i-> 99, where only 99 can do plato
+        let mut ltl = Ltl::new(0, 100, 100);
+        for state in 0..99 {
+            ltl.add_transition(state, "i", state + 1, false);
+        }
+        ltl.add_transition(99, "plato", 99, false);
+        let f = Formula::new("mu X. (<plato>true || <i>X)".to_string(), false);
+        let (result, updates) = execute_alternation_free(&f, &ltl).unwrap();
+        assert_eq!(result, (0..100).collect::<HashSet<i64>>());
+        // every state changes the fixed point, the disjunction and one of its two modalities once, and true is true
+        assert_eq!(updates, 4 * 100);
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod test_aut {
+    use model_checker::aut::{parse_aut_file, read_aut_file};
+    use model_checker::solver::execute;
+    use model_checker::types::formula::Formula;
+
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn write_temp_file(name: &str, contents: &str) -> PathBuf {
+        let file_path = std::env::temp_dir().join(format!("model_checker_{}_{}", std::process::id(), name));
+        fs::write(&file_path, contents).unwrap();
+        file_path
+    }
+
+    #[test]
+    fn test_first_transition_is_read() {
+        // only the header is skipped, the transition on the line after it is the only way out of the initial state
+        let file_path = write_temp_file("first.aut", "des (0,2,3)\n(0,\"a\",1)\n(1,\"b\",2)\n");
+
+        let (header, transitions) = parse_aut_file(file_path.clone());
+        assert_eq!(header, (0, 2, 3));
+        assert_eq!(transitions, vec![(0, String::from("a"), 1), (1, String::from("b"), 2)]);
+
+        let ltl = read_aut_file(file_path.clone(), false);
+        let (result, _) = execute(Formula::new(String::from("<a><b>true"), false), &ltl);
+        assert_eq!(result, HashSet::from([0]));
+        fs::remove_file(file_path).unwrap();
+    }
+}
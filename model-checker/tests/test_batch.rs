@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod test_batch {
+    use model_checker::batch::execute_batch;
+    use model_checker::generators::dining_philosophers;
+    use model_checker::solver::{execute, Options};
+    use model_checker::types::formula::Formula;
+
+    #[test]
+    fn test_same_result_as_one_by_one() {
+        // the formulas of input/part2-1
+        let formulas = [
+            "nu X. (([i]X && ([plato]X && [others]X )) && mu Y. ([i]Y && (<plato>true || <others>true)) )",
+            "nu X. (([i]X && [others]X ) && nu Y. (<i>Y || <others>Y) )",
+            "nu X. (([i]X && ([plato]X && [others]X )) && mu Y. ((<i>Y || <others>Y) || <plato>true ))",
+            "nu X. mu Y. ( (<plato>X || <i>Y) || <others>Y)",
+        ];
+        let ltl = dining_philosophers(3);
+
+        let batch = execute_batch(formulas.iter().map(|text| Formula::new(text.to_string(), false)).collect(), &ltl, &Options::default());
+
+        assert_eq!(batch.len(), formulas.len());
+        for (text, (result, statistics)) in formulas.iter().zip(batch.iter()) {
+            let (expected, expected_statistics) = execute(Formula::new(text.to_string(), false), &ltl);
+            assert_eq!(result, &expected, "{}", text);
+            assert!(statistics.iterations <= expected_statistics.iterations, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_shared_closed_subformula() {
+        let ltl = dining_philosophers(2);
+        // the closed fixed point is calculated for the first formula only, X is not closed so the second one calculates it
+        let formulas = vec![
+            Formula::new(String::from("mu Y. (<i>Y || <plato>true)"), false),
+            Formula::new(String::from("nu X. ([i]X && mu Y. (<i>Y || <plato>true))"), false),
+            Formula::new(String::from("nu X. ([i]X && mu Y. (<i>Y || <plato>true))"), false),
+        ];
+
+        let batch = execute_batch(formulas, &ltl, &Options::default());
+
+        let (first, _) = execute(Formula::new(String::from("mu Y. (<i>Y || <plato>true)"), false), &ltl);
+        assert_eq!(batch[0].0, first);
+        let (second, second_statistics) = execute(Formula::new(String::from("nu X. ([i]X && mu Y. (<i>Y || <plato>true))"), false), &ltl);
+        assert_eq!((&batch[1].0, &batch[2].0), (&second, &second));
+        let inner_iterations = batch[0].1.iterations;
+        assert_eq!(batch[1].1.iterations, second_statistics.iterations - inner_iterations);
+        // the whole third formula is closed and the same as the second one
+        assert_eq!((batch[2].1.iterations, batch[2].1.cache_hits), (0, 1));
+    }
+}
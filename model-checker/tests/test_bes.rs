@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod test_bes {
+    use model_checker::bes::{Bes, execute_gauss, solve_gauss, solve_local, to_bes};
+    use model_checker::generators::random_lts;
+    use model_checker::solver::execute_improved;
+    use model_checker::types::formula::Formula;
+
+    #[test]
+    fn test_same_result_as_global() {
+        let formulas = [
+            "mu X. (<a0>true || <a1>X)",
+            "nu X. mu Y. ((<a0>X || <a1>Y) || <a2>Y)",
+            "mu X. nu Y. (([a0]X && [a1]Y) && <a2>true)",
+            "(nu X. ([a1]X && mu Y. (<a0>true || <a2>Y)) && <a1>true)",
+            "nu X. (<a0>X && Z)",
+        ];
+
+        for seed in 0..3 {
+            let ltl = random_lts(8, 2, 3, seed);
+            for text in formulas {
+                let (expected, _) = execute_improved(Formula::new(text.to_string(), false), &ltl);
+                let (result, _) = execute_gauss(Formula::new(text.to_string(), false), &ltl);
+                assert_eq!(result, expected, "{} on seed {}", text, seed);
+
+                let bes = to_bes(&Formula::new(text.to_string(), false), &ltl);
+                for state in 0..ltl.nr_of_states {
+                    assert_eq!(solve_local(&bes, state as usize), expected.contains(&state), "{} in state {} of seed {}", text, state, seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_alternation() {
+        // the first equation takes precedence, so X and Y are true when X is a greatest fixed point and false otherwise
+        let bes = Bes::parse("pbes\n  nu X = Y;\n  mu Y = X;\n\ninit X;\n");
+        assert_eq!(solve_gauss(&bes), vec![true, true]);
+        assert!(solve_local(&bes, bes.init));
+
+        let bes = Bes::parse("pbes mu X = Y; nu Y = X; init X;");
+        assert_eq!(solve_gauss(&bes), vec![false, false]);
+        assert!(!solve_local(&bes, bes.init));
+    }
+
+    #[test]
+    fn test_write_and_parse() {
+        let text = "pbes\n  nu X_0 = (X_1 && Y_0);\n  nu X_1 = true;\n  mu Y_0 = (X_0 || (Y_0 && X_1));\n\ninit X_0;\n";
+
+        let bes = Bes::parse(&format!("% a comment\n{}", text));
+        let mut output: Vec<u8> = Vec::new();
+        bes.write(&mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), text);
+    }
+}
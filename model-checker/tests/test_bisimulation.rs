@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod test_branching_bisimulation_reduction {
+    use model_checker::bisimulation::{branching_bisimulation_reduction, preserves_formula};
+    use model_checker::solver::execute;
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+    use std::collections::HashMap;
+    use std::collections::HashSet;
+
+    fn build_ltl(first_state: i64, nr_of_states: i64, transitions: &[(i64, &str, i64)]) -> Ltl {
+        let mut ltl = Ltl::new(first_state, transitions.len() as i64, nr_of_states);
+        for (s, a, t) in transitions.iter() {
+            ltl.add_transition(*s, a, *t, false);
+        }
+        ltl
+    }
+
+    #[test]
+    fn test_inert_internal_step_is_removed() {
+        // 0 -i-> 1 -a-> 2, the i-step is inert so 0 and 1 are branching bisimilar
+        let ltl = build_ltl(0, 3, &[(0, "i", 1), (1, "a", 2)]);
+
+        let (reduced, state_map) = branching_bisimulation_reduction(&ltl, "i");
+
+        assert_eq!(reduced.nr_of_states, 2);
+        assert_eq!(state_map, vec![0, 0, 1]);
+        assert_eq!(reduced.first_state, 0);
+        assert_eq!(reduced.transitions, HashMap::from([
+            (0, HashMap::from([(String::from("a"), HashSet::from([1]))])),
+            (1, HashMap::new()),
+        ]));
+    }
+
+    #[test]
+    fn test_internal_cycle_is_collapsed() {
+        // 0 -i-> 1 -i-> 2 -i-> 0 is a cycle of internal steps, each state on it can do a
+        let ltl = build_ltl(1, 4, &[(0, "i", 1), (1, "i", 2), (2, "i", 0), (2, "a", 3)]);
+
+        let (reduced, state_map) = branching_bisimulation_reduction(&ltl, "i");
+
+        assert_eq!(reduced.nr_of_states, 2);
+        assert_eq!(state_map, vec![0, 0, 0, 1]);
+        assert_eq!(reduced.first_state, 0);
+        assert_eq!(reduced.get_labels(), HashSet::from([String::from("a")]));
+    }
+
+    #[test]
+    fn test_non_inert_internal_step_is_kept() {
+        // 0 -i-> 1 loses the option to do b, so 0 and 1 are not bisimilar
+        let ltl = build_ltl(0, 3, &[(0, "i", 1), (0, "b", 2), (1, "a", 2)]);
+
+        let (reduced, state_map) = branching_bisimulation_reduction(&ltl, "i");
+
+        assert_eq!(reduced.nr_of_states, 3);
+        assert_eq!(state_map, vec![0, 1, 2]);
+        assert_eq!(reduced.transitions.get(&0).unwrap().get("i"), Some(&HashSet::from([1])));
+    }
+
+    #[test]
+    fn test_internal_action_is_configurable() {
+        // With `tau` as internal action, `i` is just a visible action
+        let ltl = build_ltl(0, 3, &[(0, "i", 1), (1, "tau", 2)]);
+
+        let (reduced, state_map) = branching_bisimulation_reduction(&ltl, "tau");
+
+        assert_eq!(reduced.nr_of_states, 2);
+        assert_eq!(state_map, vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn test_preserves_formula() {
+        // 0 -i-> 1 -a-> 2 is reduced to 0 -a-> 1, where 0 can do a right away
+        let ltl = build_ltl(0, 3, &[(0, "i", 1), (1, "a", 2)]);
+        let f = Formula::new(String::from("<a>true"), false);
+        let (reduced, state_map) = branching_bisimulation_reduction(&ltl, "i");
+        let (result, _) = execute(Formula::new(String::from("<a>true"), false), &ltl);
+        let (reduced_result, _) = execute(Formula::new(String::from("<a>true"), false), &reduced);
+        assert!(!result.contains(&0));
+        assert!(reduced_result.contains(&state_map[0]));
+        assert!(!preserves_formula(&ltl, &f.root_node, "i"));
+
+        // without modalities, or without internal transitions, the result is the same
+        assert!(preserves_formula(&ltl, &Formula::new(String::from("nu X. X"), false).root_node, "i"));
+        assert!(preserves_formula(&ltl, &f.root_node, "tau"));
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod test_compose {
+    use model_checker::composition::compose;
+    use model_checker::types::ltl::Ltl;
+    use std::collections::HashSet;
+
+    fn build_ltl(first_state: i64, nr_of_states: i64, transitions: &[(i64, &str, i64)]) -> Ltl {
+        let mut ltl = Ltl::new(first_state, transitions.len() as i64, nr_of_states);
+        for (s, a, t) in transitions.iter() {
+            ltl.add_transition(*s, a, *t, false);
+        }
+        ltl
+    }
+
+    fn nr_of_transitions(ltl: &Ltl) -> usize {
+        ltl.transitions.values().flat_map(|m| m.values()).map(|targets| targets.len()).sum()
+    }
+
+    #[test]
+    fn test_interleaving() {
+        // Without synchronisation, a and b can happen in either order
+        let left = build_ltl(0, 2, &[(0, "a", 1)]);
+        let right = build_ltl(0, 2, &[(0, "b", 1)]);
+
+        let product = compose(&[left, right], &[HashSet::new(), HashSet::new()], "i");
+
+        assert_eq!(product.nr_of_states, 4);
+        assert_eq!(nr_of_transitions(&product), 4);
+        assert_eq!(product.transitions.get(&0).unwrap().keys().cloned().collect::<HashSet<String>>(),
+            HashSet::from([String::from("a"), String::from("b")]));
+    }
+
+    #[test]
+    fn test_synchronisation() {
+        // Both components have to do `s` together, so the left component cannot do s on its own in state 0
+        let left = build_ltl(0, 3, &[(0, "s", 1), (1, "a", 2)]);
+        let right = build_ltl(0, 2, &[(0, "b", 1), (1, "s", 0)]);
+        let sync = HashSet::from([String::from("s")]);
+
+        let product = compose(&[left, right], &[sync.clone(), sync], "i");
+
+        // (0,0) -b-> (0,1) -s-> (1,0) -a-> (2,0) -b-> (2,1), (1,0) -b-> (1,1) -a-> (2,1)
+        assert_eq!(product.nr_of_states, 6);
+        assert_eq!(nr_of_transitions(&product), 6);
+        assert_eq!(product.transitions.get(&0).unwrap().keys().cloned().collect::<HashSet<String>>(),
+            HashSet::from([String::from("b")]));
+    }
+
+    #[test]
+    fn test_multi_party_synchronisation() {
+        // Three components synchronise on s, two of them can do it in two ways
+        let first = build_ltl(0, 3, &[(0, "s", 1), (0, "s", 2)]);
+        let second = build_ltl(0, 3, &[(0, "s", 1), (0, "s", 2)]);
+        let third = build_ltl(0, 2, &[(0, "s", 1)]);
+        let sync = HashSet::from([String::from("s")]);
+
+        let product = compose(&[first, second, third], &[sync.clone(), sync.clone(), sync], "i");
+
+        assert_eq!(product.nr_of_states, 5);
+        assert_eq!(product.transitions.get(&0).unwrap().get("s").unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_only_reachable_states() {
+        // The component that cannot do s blocks it, so nothing beyond the initial state is reachable
+        let left = build_ltl(0, 3, &[(0, "s", 1), (1, "a", 2)]);
+        let right = build_ltl(0, 2, &[(1, "s", 0)]);
+        let sync = HashSet::from([String::from("s")]);
+
+        let product = compose(&[left, right], &[sync.clone(), sync], "i");
+
+        assert_eq!(product.nr_of_states, 1);
+        assert_eq!(nr_of_transitions(&product), 0);
+    }
+
+    #[test]
+    fn test_internal_action_does_not_synchronise() {
+        let left = build_ltl(0, 2, &[(0, "i", 1)]);
+        let right = build_ltl(0, 2, &[(0, "i", 1)]);
+        let sync = HashSet::from([String::from("i")]);
+
+        let product = compose(&[left, right], &[sync.clone(), sync], "i");
+
+        assert_eq!(product.nr_of_states, 4);
+    }
+
+    #[test]
+    fn test_reproducible_numbering() {
+        // The components can each choose between two labels, the composed states are numbered the same way every time
+        let build = || {
+            let left = build_ltl(0, 3, &[(0, "a", 1), (0, "b", 2), (1, "s", 0)]);
+            let right = build_ltl(0, 3, &[(0, "c", 1), (0, "d", 2), (2, "s", 0)]);
+            let sync = HashSet::from([String::from("s")]);
+            compose(&[left, right], &[sync.clone(), sync], "i")
+        };
+
+        let expected = build();
+        for _ in 0..10 {
+            assert_eq!(build().transitions, expected.transitions);
+        }
+    }
+}
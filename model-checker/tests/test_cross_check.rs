@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test_cross_check {
+    use model_checker::cross_check::{algorithms, cross_check, cross_check_with, Algorithm, Disagreement};
+    use model_checker::generators::random_lts;
+    use model_checker::solver::execute;
+    use model_checker::types::formula::{Formula, Node, formula_to_string};
+    use model_checker::types::transition_system::TransitionSystem;
+
+    use std::collections::{BTreeSet, HashSet};
+
+    // Gets a1-diamonds wrong on purpose, it takes them for boxes
+    fn wrong(node: &Node, instance: &dyn TransitionSystem) -> Option<HashSet<i64>> {
+        let text = formula_to_string(node).replace("<a1>", "[a1]");
+        Some(execute(Formula::new(text, false), instance).0)
+    }
+
+    #[test]
+    fn test_agree() {
+        let formulas = [
+            "mu X. (<a0>true || <a1>X)",
+            "nu X. mu Y. ((<a0>X || <a1>Y) || <a2>Y)",
+            "mu X. nu Y. (([a0]X && [a1]Y) && <a2>true)",
+            "nu X. (<a0>X && Z)",
+        ];
+
+        for seed in 0..3 {
+            let ltl = random_lts(8, 2, 3, seed);
+            for text in formulas {
+                let (expected, _) = execute(Formula::new(text.to_string(), false), &ltl);
+                let result = cross_check(&Formula::new(text.to_string(), false), &ltl);
+                assert_eq!(result, Ok(expected.into_iter().collect::<BTreeSet<i64>>()), "{} on seed {}", text, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_disagree() {
+        let ltl = random_lts(8, 2, 3, 0);
+        let text = "mu X. (<a1>true || <a0>X)";
+        let algorithms = [algorithms()[0], Algorithm { name: "wrong", run: wrong }];
+
+        let result = cross_check_with(&Formula::new(text.to_string(), false), &ltl, &algorithms);
+
+        // the smallest subformula they disagree on is <a1>true, on the states without an a1-transition
+        let (expected, _) = execute(Formula::new(String::from("<a1>true"), false), &ltl);
+        let all: BTreeSet<i64> = ltl.get_all_states().into_iter().collect();
+        let expected: BTreeSet<i64> = expected.into_iter().collect();
+        assert!(expected != all);
+        assert_eq!(result, Err(Disagreement {
+            subformula: String::from("<a1>true"),
+            results: vec![("naive", expected.clone()), ("wrong", all.clone())],
+            states: all.difference(&expected).cloned().collect(),
+        }));
+    }
+
+    #[test]
+    fn test_large_instance() {
+        let ltl = random_lts(501, 2, 3, 0);
+        let f = Formula::new(String::from("mu X. (<a0>true || <a1>X)"), false);
+
+        // local and Gauss elimination are left out
+        let applied: Vec<&str> = algorithms().into_iter()
+            .filter(|algorithm| (algorithm.run)(&f.root_node, &ltl).is_some())
+            .map(|algorithm| algorithm.name)
+            .collect();
+        assert_eq!(applied, vec!["naive", "improved", "symbolic", "alt-free"]);
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod test_diagnose {
+    use model_checker::diagnostics::diagnose;
+    use model_checker::types::ltl::Ltl;
+
+    #[test]
+    fn test_diagnose() {
+        let mut simple_ltl = Ltl::new(0, 7, 6);
+        let transitions: [(i64, &str, i64); 7] = [
+            (0, "i", 1),
+            (1, "i", 0),
+            (0, "a", 2),
+            (0, "a", 3),
+            (2, "b", 2),
+            (3, "i", 4),
+            (4, "i", 4),
+        ];
+        for (s, a, t) in transitions.iter() {
+            simple_ltl.add_transition(*s, a, *t, false);
+        }
+
+        let diagnostics = diagnose(&simple_ltl, "i");
+
+        assert_eq!(diagnostics.nr_of_states, 6);
+        assert_eq!(diagnostics.nr_of_transitions, 7);
+        assert_eq!(diagnostics.deadlock_states, vec![5]);
+        assert_eq!(diagnostics.label_histogram, vec![
+            (String::from("i"), 4),
+            (String::from("a"), 2),
+            (String::from("b"), 1),
+        ]);
+        assert_eq!((diagnostics.max_out_degree, diagnostics.max_out_degree_state), (3, 0));
+        // {0,1}, {2}, {3}, {4}, {5}
+        assert_eq!(diagnostics.nr_of_sccs, 5);
+        assert_eq!(diagnostics.largest_scc, 2);
+        assert_eq!(diagnostics.bottom_sccs, vec![vec![2], vec![4], vec![5]]);
+        // 0 and 1 are on an internal cycle, 4 has an internal self loop
+        assert_eq!(diagnostics.divergent_states, vec![0, 1, 4]);
+    }
+}
+
+
+#[cfg(test)]
+mod test_check_aut_header {
+    use model_checker::diagnostics::check_aut_header;
+
+    #[test]
+    fn test_matching_header() {
+        let transitions = vec![(0, String::from("a"), 1), (1, String::from("b"), 0)];
+
+        assert!(check_aut_header((0, 2, 2), &transitions).is_empty());
+    }
+
+    #[test]
+    fn test_mismatching_header() {
+        let transitions = vec![
+            (0, String::from("a"), 1),
+            (0, String::from("a"), 1),
+            (1, String::from("b"), 5),
+        ];
+
+        let mismatches = check_aut_header((7, 2, 4), &transitions);
+
+        assert_eq!(mismatches, vec![
+            String::from("the initial state 7 is not one of the 4 states"),
+            String::from("the header declares 2 transitions, but the body has 3"),
+            String::from("transition (1,\"b\",5) uses state 5, but the header declares 4 states"),
+            String::from("the body contains 1 duplicate transitions"),
+        ]);
+    }
+}
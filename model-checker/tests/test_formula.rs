@@ -64,3 +64,26 @@ mod formula_tests {
         }
     }
 }
+
+
+#[cfg(test)]
+mod formula_helper_tests {
+    use model_checker::types::formula::{formula_to_string, find_free_variables, find_actions, Formula};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_formula_to_string() {
+        let f = Formula::new(String::from("nu X. (([i]X && [plato]X) && mu Y. (<i>Y || <plato>true))"), false);
+
+        assert_eq!(formula_to_string(&f.root_node), "nu X. (([i]X && [plato]X) && mu Y. (<i>Y || <plato>true))");
+    }
+
+    #[test]
+    fn test_find_free_variables_and_actions() {
+        let f = Formula::new(String::from("nu X. (([i]X && [plato]Z) && mu Y. (<i>Y || <others>X))"), false);
+
+        assert_eq!(find_free_variables(&f.root_node), HashSet::from([String::from("Z")]));
+        assert_eq!(find_actions(&f.root_node),
+            HashSet::from([String::from("i"), String::from("plato"), String::from("others")]));
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test_generators {
+    use model_checker::aut::read_aut_file;
+    use model_checker::diagnostics::diagnose;
+    use model_checker::generators::{dining_philosophers, demanding_children, mutex_ring, random_lts};
+    use model_checker::refinement::{check_refinement, RefinementModel};
+    use model_checker::types::ltl::Ltl;
+
+    use std::path::PathBuf;
+
+    fn nr_of_transitions(ltl: &Ltl) -> usize {
+        ltl.transitions.values().flat_map(|m| m.values()).map(|targets| targets.len()).sum()
+    }
+
+    // Both LTSs have the same size and the same stable failures, they only number their states differently
+    fn assert_equivalent(generated: &Ltl, checked_in: &Ltl) {
+        assert_eq!(generated.nr_of_states, checked_in.nr_of_states);
+        assert_eq!(nr_of_transitions(generated), nr_of_transitions(checked_in));
+        assert_eq!(check_refinement(generated, checked_in, RefinementModel::Failures, "i"), None);
+        assert_eq!(check_refinement(checked_in, generated, RefinementModel::Failures, "i"), None);
+    }
+
+    #[test]
+    fn test_dining_philosophers() {
+        for n in 2..=4 {
+            let checked_in = read_aut_file(PathBuf::from(format!("input/part2-1/dining_{}.aut", n)), false);
+            assert_equivalent(&dining_philosophers(n), &checked_in);
+        }
+    }
+
+    #[test]
+    fn test_demanding_children() {
+        for n in 2..=4 {
+            let checked_in = read_aut_file(PathBuf::from(format!("input/part2-2/demanding_children_{}.aut", n)), false);
+            assert_equivalent(&demanding_children(n), &checked_in);
+        }
+    }
+
+    #[test]
+    fn test_mutex_ring() {
+        let ltl = mutex_ring(3);
+        let diagnostics = diagnose(&ltl, "i");
+
+        // 3 token positions, each with 3 * 2 * 2 local states of the holder and the others
+        assert_eq!(ltl.nr_of_states, 36);
+        assert!(diagnostics.deadlock_states.is_empty());
+        assert_eq!(diagnostics.nr_of_sccs, 1);
+    }
+
+    #[test]
+    fn test_random_lts() {
+        let ltl = random_lts(50, 2, 3, 7);
+        let same = random_lts(50, 2, 3, 7);
+        let other = random_lts(50, 2, 3, 8);
+
+        assert_eq!(ltl.transitions, same.transitions);
+        assert_ne!(ltl.transitions, other.transitions);
+        assert!(ltl.get_labels().iter().all(|label| ["a0", "a1", "a2"].contains(&label.as_str())));
+        // every state is reachable from the initial state
+        let mut reached = [false; 50];
+        reached[0] = true;
+        let mut todo = vec![0];
+        while let Some(state) = todo.pop() {
+            for target in ltl.transitions[&state].values().flatten() {
+                if !reached[*target as usize] {
+                    reached[*target as usize] = true;
+                    todo.push(*target);
+                }
+            }
+        }
+        assert!(reached.iter().all(|r| *r));
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod test_incremental {
+    use model_checker::generators::random_lts;
+    use model_checker::incremental::{apply_changes, Change, CheckedFormula};
+    use model_checker::solver::execute;
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+    use model_checker::types::transition_system::TransitionSystem;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_same_result_as_from_scratch() {
+        let formulas = [
+            "mu X. (<a0>true || <a1>X)",
+            "nu X. ([a0]X && <a1>true)",
+            "nu X. mu Y. ((<a0>X || <a1>Y) || <a2>Y)",
+            "mu X. nu Y. (([a0]X && [a1]Y) && <a2>true)",
+            "(nu X. ([a1]X && mu Y. (<a0>true || <a2>Y)) && <a1>true)",
+            "mu X. mu Y. ((<a0>X || <a1>Y) || [a2]false)",
+        ];
+
+        for seed in 0..3 {
+            let mut ltl = random_lts(30, 2, 3, seed);
+            let mut checked: Vec<CheckedFormula> = formulas.iter()
+                .map(|text| CheckedFormula::check(Formula::new(text.to_string(), false), &ltl))
+                .collect();
+            let mut rng = StdRng::seed_from_u64(seed);
+            // rounds that only add transitions, only remove them, and both
+            for (add, remove) in [(true, false), (false, true), (true, true), (true, false)] {
+                let mut changes: Vec<Change> = Vec::new();
+                for _ in 0..4 {
+                    let source = rng.gen_range(0..30);
+                    let label = format!("a{}", rng.gen_range(0..3));
+                    if add && (!remove || rng.gen_bool(0.5)) {
+                        changes.push(Change::Add(source, label, rng.gen_range(0..30)));
+                    } else if let Some(target) = ltl.successors(source, &label).first() {
+                        changes.push(Change::Remove(source, label, *target));
+                    }
+                }
+                let applied = apply_changes(&mut ltl, &changes);
+                for (text, checked) in formulas.iter().zip(checked.iter_mut()) {
+                    let (expected, _) = execute(Formula::new(text.to_string(), false), &ltl);
+                    assert_eq!(checked.recheck(&ltl, &applied), &expected, "{} after {:?} on seed {}", text, applied, seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_warm_start() {
+        // a chain 0 -i-> 1 -i-> ... -i-> 9, where 5 can do plato
+        let mut ltl = Ltl::new(0, 10, 10);
+        for state in 0..9 {
+            ltl.add_transition(state, "i", state + 1, false);
+        }
+        ltl.add_transition(5, "plato", 5, false);
+        let text = "mu X. (<plato>true || <i>X)";
+        let mut checked = CheckedFormula::check(Formula::new(text.to_string(), false), &ltl);
+        assert_eq!(checked.result, (0..6).collect::<HashSet<i64>>());
+
+        // X starts from {0, ..., 5}, so only 6, 7, 8 and 9 are added, where from scratch it takes 6 iterations to add 0
+        let applied = apply_changes(&mut ltl, &[Change::Add(9, String::from("plato"), 9), Change::Add(5, String::from("plato"), 5)]);
+        assert_eq!(applied, vec![Change::Add(9, String::from("plato"), 9)]);
+        assert_eq!(checked.recheck(&ltl, &applied), &(0..10).collect::<HashSet<i64>>());
+        assert_eq!(checked.statistics.iterations, 5);
+        let (_, statistics) = execute(Formula::new(text.to_string(), false), &ltl);
+        assert_eq!(statistics.iterations, 7);
+
+        // the formula has no a, and removing plato makes X smaller, so it starts from scratch
+        let applied = apply_changes(&mut ltl, &[Change::Add(0, String::from("a"), 1)]);
+        assert_eq!(checked.recheck(&ltl, &applied), &(0..10).collect::<HashSet<i64>>());
+        assert_eq!(checked.statistics.iterations, 0);
+        let applied = apply_changes(&mut ltl, &[Change::Remove(5, String::from("plato"), 5)]);
+        assert_eq!(checked.recheck(&ltl, &applied), &(0..10).collect::<HashSet<i64>>());
+        assert_eq!(checked.statistics.iterations, 11);
+    }
+
+    #[test]
+    fn test_start_value_one() {
+        // the old value of X is {1}, which is not its new value
+        let mut ltl = Ltl::new(0, 1, 3);
+        ltl.add_transition(1, "a1", 0, false);
+        let mut checked = CheckedFormula::check(Formula::new(String::from("mu X. <a1>(true || false)"), false), &ltl);
+        assert_eq!(checked.result, HashSet::from([1]));
+        let applied = apply_changes(&mut ltl, &[Change::Add(0, String::from("a1"), 0), Change::Add(0, String::from("a1"), 2)]);
+        assert_eq!(checked.recheck(&ltl, &applied), &HashSet::from([0, 1]));
+
+        let mut ltl = Ltl::new(0, 1, 3);
+        ltl.add_transition(1, "a2", 1, false);
+        let mut checked = CheckedFormula::check(Formula::new(String::from("nu X. <a2>(X && X)"), false), &ltl);
+        assert_eq!(checked.result, HashSet::from([1]));
+        let applied = apply_changes(&mut ltl, &[Change::Remove(1, String::from("a2"), 1)]);
+        assert_eq!(checked.recheck(&ltl, &applied), &HashSet::new());
+    }
+
+    #[test]
+    fn test_same_result_as_check_on_small_instances() {
+        // on a few states the old values are often small sets such as {1}
+        let formulas = [
+            "mu X. <a1>(true || false)",
+            "nu X. <a2>(X && X)",
+            "mu X. (<a0>X || [a1]false)",
+            "nu X. ([a0]X && <a2>true)",
+            "nu X. mu Y. (<a0>X || <a1>Y)",
+        ];
+
+        for seed in 0..20 {
+            let mut ltl = random_lts(4, 1, 3, seed);
+            let mut checked: Vec<CheckedFormula> = formulas.iter()
+                .map(|text| CheckedFormula::check(Formula::new(text.to_string(), false), &ltl))
+                .collect();
+            let mut rng = StdRng::seed_from_u64(seed);
+            for (add, remove) in [(true, false), (false, true), (true, false), (false, true), (true, true)] {
+                let mut changes: Vec<Change> = Vec::new();
+                for _ in 0..2 {
+                    let source = rng.gen_range(0..4);
+                    let label = format!("a{}", rng.gen_range(0..3));
+                    if add && (!remove || rng.gen_bool(0.5)) {
+                        changes.push(Change::Add(source, label, rng.gen_range(0..4)));
+                    } else if let Some(target) = ltl.successors(source, &label).first() {
+                        changes.push(Change::Remove(source, label, *target));
+                    }
+                }
+                let applied = apply_changes(&mut ltl, &changes);
+                for (text, checked) in formulas.iter().zip(checked.iter_mut()) {
+                    let expected = CheckedFormula::check(Formula::new(text.to_string(), false), &ltl).result;
+                    assert_eq!(checked.recheck(&ltl, &applied), &expected, "{} after {:?} on seed {}", text, applied, seed);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod test_local {
+    use model_checker::generators::random_lts;
+    use model_checker::local::execute_local;
+    use model_checker::solver::{execute, execute_improved};
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+
+    #[test]
+    fn test_same_result_as_global() {
+        let formulas = [
+            "mu X. (<a0>true || <a1>X)",
+            "nu X. ([a0]X && <a1>true)",
+            "nu X. mu Y. ((<a0>X || <a1>Y) || <a2>Y)",
+            "mu X. nu Y. (([a0]X && [a1]Y) && <a2>true)",
+            "(nu X. ([a1]X && mu Y. (<a0>true || <a2>Y)) && <a1>true)",
+            "mu X. mu Y. ((<a0>X || <a1>Y) || [a2]false)",
+            "nu X. (<a0>X && Z)",
+        ];
+
+        for seed in 0..5 {
+            let ltl = random_lts(30, 2, 3, seed);
+            for text in formulas {
+                let (expected, _) = execute_improved(Formula::new(text.to_string(), false), &ltl);
+                for state in 0..ltl.nr_of_states {
+                    let (holds, _) = execute_local(Formula::new(text.to_string(), false), &ltl, state);
+                    assert_eq!(holds, expected.contains(&state), "{} in state {} of seed {}", text, state, seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_result_as_naive() {
+        // closed subformulas with alternating fixed points are solved globally, nested in various ways
+        let formulas = [
+            "mu X. nu Y. nu Z. mu W. <a2>Y",
+            "nu X. mu Y. nu Z. ((<a0>X || <a1>Y) && [a2]Z)",
+            "mu X. nu Y. mu Z. ((<a0>X && <a1>Y) || <a2>Z)",
+            "nu X. mu Y. nu Z. mu W. (<a0>X || (<a1>Y && (<a2>Z || <a0>W)))",
+            "(nu X. mu Y. ((<a0>X || <a1>Y) || <a2>Y) && <a1>true)",
+            "[a0]mu X. ([a1]X && nu Y. mu Z. ((<a2>Y || <a0>Z) && <a1>X))",
+        ];
+
+        for seed in (0..40).chain([303084]) {
+            let ltl = random_lts(6, 1 + seed as usize % 3, 3, seed);
+            for text in formulas {
+                let (expected, _) = execute(Formula::new(text.to_string(), false), &ltl);
+                for state in 0..ltl.nr_of_states {
+                    let (holds, _) = execute_local(Formula::new(text.to_string(), false), &ltl, state);
+                    assert_eq!(holds, expected.contains(&state), "{} in state {} of seed {}", text, state, seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stops_early() {
+        // a long chain 0 -a-> 1 -a-> ... -a-> 999, where 0 can also do b
+        let mut ltl = Ltl::new(0, 1000, 1000);
+        for state in 0..999 {
+            ltl.add_transition(state, "a", state + 1, false);
+        }
+        ltl.add_transition(0, "b", 999, false);
+
+        let (holds, explored) = execute_local(Formula::new(String::from("mu X. (<b>true || <a>X)"), false), &ltl, 0);
+
+        assert!(holds);
+        // only the first few states of the chain are explored
+        assert!(explored < 20);
+    }
+}
@@ -78,6 +78,31 @@ mod test_insert_transition {
         let mut simple_ltl: Ltl = Ltl::new(0, 2, 3);
         simple_ltl.add_transition(1, "a", 3, false); // 1 -a-> 3, but 3 is too big
     }
+
+    #[test]
+    fn test_remove_transition() {
+        let mut simple_ltl: Ltl = Ltl::new(0, 2, 3);
+        simple_ltl.add_transition(0, "a", 1, false); // 0 -a-> 1
+        simple_ltl.add_transition(0, "a", 2, false); // 0 -a-> 2
+
+        assert!(simple_ltl.remove_transition(0, "a", 1, false));
+        assert_eq!(simple_ltl.transitions[&0], HashMap::from([(String::from("a"), HashSet::from([2]))]));
+
+        // Removing a transition that is not there does not change the data
+        assert!(!simple_ltl.remove_transition(0, "a", 1, false));
+        assert!(!simple_ltl.remove_transition(1, "b", 0, false));
+
+        // Removing the last a-transition removes the label
+        assert!(simple_ltl.remove_transition(0, "a", 2, false));
+        assert_eq!(simple_ltl.transitions[&0], HashMap::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "end_state '3' not correct")]
+    fn test_panic_remove_end_state_too_big() {
+        let mut simple_ltl: Ltl = Ltl::new(0, 2, 3);
+        simple_ltl.remove_transition(1, "a", 3, false); // 1 -a-> 3, but 3 is too big
+    }
 }
 
 
@@ -246,3 +271,64 @@ mod test_get_diamond_modality {
         assert_eq!(diamod, HashSet::from([]))
     }
 }
+
+
+#[cfg(test)]
+mod test_hide {
+    use model_checker::types::ltl::Ltl;
+    use model_checker::types::label_pattern::LabelPattern;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_hide_exact_and_regex() {
+        let mut simple_ltl = Ltl::new(0, 3, 4);
+        simple_ltl.add_transition(0, "lock(p1, f1)", 1, false);
+        simple_ltl.add_transition(1, "eat(p1)", 2, false);
+        simple_ltl.add_transition(2, "free(p1, f1)", 3, false);
+
+        let hidden = simple_ltl.hide(&[LabelPattern::new("eat(p1)"), LabelPattern::new("/free\\(.*\\)/")], "i");
+
+        assert_eq!(hidden.get_labels(), HashSet::from([String::from("lock(p1, f1)"), String::from("i")]));
+        assert_eq!(hidden.transitions.get(&1).unwrap().get("i"), Some(&HashSet::from([2])));
+        assert_eq!(hidden.transitions.get(&2).unwrap().get("i"), Some(&HashSet::from([3])));
+        assert_eq!(hidden.first_state, 0);
+        assert_eq!(hidden.nr_of_states, 4);
+    }
+}
+
+
+#[cfg(test)]
+mod test_rename {
+    use model_checker::types::ltl::Ltl;
+    use model_checker::types::label_pattern::LabelPattern;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_rename_merges_labels() {
+        let mut simple_ltl = Ltl::new(0, 3, 3);
+        simple_ltl.add_transition(0, "lock(p1, f1)", 1, false);
+        simple_ltl.add_transition(0, "lock(p2, f2)", 2, false);
+        simple_ltl.add_transition(1, "eat(p1)", 2, false);
+
+        // lock(p1, f1) and lock(p2, f2) both become lock
+        let renamed = simple_ltl.rename(&[(LabelPattern::new("/(lock)\\(.*\\)/"), String::from("$1"))]);
+
+        assert_eq!(renamed.get_labels(), HashSet::from([String::from("lock"), String::from("eat(p1)")]));
+        assert_eq!(renamed.transitions.get(&0).unwrap().get("lock"), Some(&HashSet::from([1, 2])));
+    }
+
+    #[test]
+    fn test_rename_first_matching_rule() {
+        let mut simple_ltl = Ltl::new(0, 2, 3);
+        simple_ltl.add_transition(0, "a", 1, false);
+        simple_ltl.add_transition(1, "b", 2, false);
+
+        let renamed = simple_ltl.rename(&[
+            (LabelPattern::new("a"), String::from("c")),
+            (LabelPattern::new("/a|b/"), String::from("d")),
+        ]);
+
+        assert_eq!(renamed.transitions.get(&0).unwrap().get("c"), Some(&HashSet::from([1])));
+        assert_eq!(renamed.transitions.get(&1).unwrap().get("d"), Some(&HashSet::from([2])));
+    }
+}
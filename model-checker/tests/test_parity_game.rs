@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod test_parity_game {
+    use model_checker::parity_game::to_parity_game;
+    use model_checker::solver::execute;
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+
+    use std::collections::HashSet;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn build_ltl() -> Ltl {
+        // 0 -a-> 1 -a-> 0, and 1 -b-> 2, where 2 is a deadlock
+        let mut simple_ltl = Ltl::new(0, 3, 3);
+        let transitions: [(i64, &str, i64); 3] = [
+            (0, "a", 1),
+            (1, "a", 0),
+            (1, "b", 2),
+        ];
+        for (s, a, t) in transitions.iter() {
+            simple_ltl.add_transition(*s, a, *t, false);
+        }
+        simple_ltl
+    }
+
+    #[test]
+    fn test_game() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("nu X. ([a]X && mu Y. (<b>true || <a>Y))"), false);
+
+        let game = to_parity_game(&f, &ltl);
+
+        assert_eq!(game.subformulas, vec![
+            "nu X. ([a]X && mu Y. (<b>true || <a>Y))",
+            "([a]X && mu Y. (<b>true || <a>Y))",
+            "[a]X",
+            "mu Y. (<b>true || <a>Y)",
+            "(<b>true || <a>Y)",
+            "<b>true",
+            "true",
+            "<a>Y",
+        ]);
+        // the first vertices are the states and the formula
+        let name = |index: usize, state: i64| format!("[{}] |= {}", game.subformulas[index], state);
+        for state in 0..3 {
+            assert_eq!(game.vertices[state as usize].name, name(0, state));
+        }
+
+        let vertex = |index: usize, state: i64| game.vertices.iter().position(|vertex| vertex.name == name(index, state)).unwrap();
+        // the greatest fixed point is even, the least fixed point inside it odd
+        assert_eq!(game.vertices[vertex(0, 0)].priority, 0);
+        assert_eq!(game.vertices[vertex(3, 0)].priority, 1);
+        // [a]X is owned by player 1 and refers back to X, <a>Y by player 0 and refers back to Y
+        assert_eq!(game.vertices[vertex(2, 0)].owner, 1);
+        assert_eq!(game.vertices[vertex(2, 0)].successors, vec![vertex(0, 1)]);
+        assert_eq!(game.vertices[vertex(7, 0)].owner, 0);
+        assert_eq!(game.vertices[vertex(7, 0)].successors, vec![vertex(3, 1)]);
+        // state 0 cannot do b, so player 0 loses <b>true there
+        let stuck = vertex(5, 0);
+        assert_eq!(game.vertices[stuck].successors, vec![stuck]);
+        assert_eq!(game.vertices[stuck].priority, 1);
+        // true is won by player 0
+        let truth = vertex(6, 2);
+        assert_eq!(game.vertices[truth].successors, vec![truth]);
+        assert_eq!(game.vertices[truth].priority, 0);
+    }
+
+    #[test]
+    fn test_write() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("<b>true"), false);
+
+        let mut output: Vec<u8> = Vec::new();
+        to_parity_game(&f, &ltl).write(&mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "parity 3;\n\
+            0 1 0 0 \"[<b>true] |= 0\";\n\
+            1 0 0 3 \"[<b>true] |= 1\";\n\
+            2 1 0 2 \"[<b>true] |= 2\";\n\
+            3 0 1 3 \"[true] |= 2\";\n");
+    }
+
+    #[test]
+    fn test_parity_game_solver() {
+        let ltl = build_ltl();
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("../parity-game-solver/Cargo.toml");
+        let file_path = std::env::temp_dir().join(format!("model_checker_{}_game.gm", std::process::id()));
+
+        // the parity game solver does not solve games with odd priorities correctly yet, so there are no least fixed points
+        for text in ["nu X. ([a]X && <a>true)", "<a><b>true"] {
+            let mut contents: Vec<u8> = Vec::new();
+            to_parity_game(&Formula::new(text.to_string(), false), &ltl).write(&mut contents).unwrap();
+            std::fs::write(&file_path, contents).unwrap();
+            let output = Command::new(env!("CARGO"))
+                .args(["run", "--quiet", "--manifest-path"]).arg(&manifest)
+                .arg("--").arg("--gm-file").arg(&file_path)
+                .output()
+                .expect("Should have been able to run the parity game solver");
+            std::fs::remove_file(&file_path).unwrap();
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+            // the solver prints the measure of every vertex, which is T for the vertices player 1 wins
+            let won: HashSet<i64> = String::from_utf8(output.stdout).unwrap().lines()
+                .filter_map(|line| line.split_once(" | "))
+                .filter(|(_, measure)| *measure != "T")
+                .filter_map(|(identifier, _)| identifier.trim().parse::<i64>().ok())
+                .filter(|identifier| *identifier < ltl.nr_of_states)
+                .collect();
+            let (expected, _) = execute(Formula::new(text.to_string(), false), &ltl);
+            assert_eq!(won, expected, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod test_refinement {
+    use model_checker::refinement::{check_refinement, Counterexample, RefinementModel, Violation};
+    use model_checker::types::ltl::Ltl;
+
+    use std::collections::BTreeSet;
+
+    fn build_ltl(nr_of_states: i64, transitions: &[(i64, &str, i64)]) -> Ltl {
+        let mut ltl = Ltl::new(0, transitions.len() as i64, nr_of_states);
+        for (s, a, t) in transitions.iter() {
+            ltl.add_transition(*s, a, *t, false);
+        }
+        ltl
+    }
+
+    #[test]
+    fn test_trace_inclusion_holds() {
+        // the specification can choose between b and c after a, the implementation always does b
+        let specification = build_ltl(4, &[(0, "a", 1), (1, "b", 2), (1, "c", 3), (2, "a", 1)]);
+        let implementation = build_ltl(2, &[(0, "a", 1), (1, "b", 0)]);
+
+        assert_eq!(check_refinement(&implementation, &specification, RefinementModel::Traces, "i"), None);
+        // but after a it refuses c, which the specification does not allow
+        assert_eq!(check_refinement(&implementation, &specification, RefinementModel::Failures, "i"), Some(Counterexample {
+            trace: vec![String::from("a")],
+            violation: Violation::Refusal(BTreeSet::from([String::from("a"), String::from("c")])),
+        }));
+    }
+
+    #[test]
+    fn test_shortest_trace_violation() {
+        let specification = build_ltl(2, &[(0, "a", 1), (1, "b", 0)]);
+        // a long and a short way to do a second a
+        let implementation = build_ltl(5, &[(0, "a", 1), (1, "b", 2), (2, "a", 3), (3, "b", 4), (4, "b", 0), (1, "a", 0)]);
+
+        assert_eq!(check_refinement(&implementation, &specification, RefinementModel::Traces, "i"), Some(Counterexample {
+            trace: vec![String::from("a"), String::from("a")],
+            violation: Violation::Trace,
+        }));
+    }
+
+    #[test]
+    fn test_shortest_failures_violation() {
+        // after a the implementation does c, which the specification does not allow, but after b it already refuses d
+        let specification = build_ltl(4, &[(0, "a", 1), (0, "b", 2), (2, "d", 3)]);
+        let implementation = build_ltl(4, &[(0, "a", 1), (0, "b", 2), (1, "c", 3)]);
+
+        assert_eq!(check_refinement(&implementation, &specification, RefinementModel::Failures, "i"), Some(Counterexample {
+            trace: vec![String::from("b")],
+            violation: Violation::Refusal(["a", "b", "c", "d"].into_iter().map(String::from).collect()),
+        }));
+        assert_eq!(check_refinement(&implementation, &specification, RefinementModel::Traces, "i"), Some(Counterexample {
+            trace: vec![String::from("a"), String::from("c")],
+            violation: Violation::Trace,
+        }));
+    }
+
+    #[test]
+    fn test_internal_steps_are_not_observed() {
+        let specification = build_ltl(3, &[(0, "a", 1), (1, "i", 2), (2, "b", 0)]);
+        let implementation = build_ltl(3, &[(0, "i", 1), (1, "a", 2), (2, "b", 0)]);
+
+        assert_eq!(check_refinement(&implementation, &specification, RefinementModel::Traces, "i"), None);
+        assert_eq!(check_refinement(&implementation, &specification, RefinementModel::Failures, "i"), None);
+        assert_eq!(check_refinement(&specification, &implementation, RefinementModel::Failures, "i"), None);
+    }
+
+    #[test]
+    fn test_nondeterministic_specification() {
+        // the specification decides internally between a and b, so it may refuse either
+        let specification = build_ltl(5, &[(0, "i", 1), (0, "i", 2), (1, "a", 3), (2, "b", 4)]);
+        let only_a = build_ltl(2, &[(0, "a", 1)]);
+        let both = build_ltl(3, &[(0, "a", 1), (0, "b", 2)]);
+        let neither = build_ltl(1, &[]);
+
+        assert_eq!(check_refinement(&only_a, &specification, RefinementModel::Failures, "i"), None);
+        assert_eq!(check_refinement(&both, &specification, RefinementModel::Failures, "i"), None);
+        assert_eq!(check_refinement(&neither, &specification, RefinementModel::Traces, "i"), None);
+        assert_eq!(check_refinement(&neither, &specification, RefinementModel::Failures, "i"), Some(Counterexample {
+            trace: vec![],
+            violation: Violation::Refusal(BTreeSet::from([String::from("a"), String::from("b")])),
+        }));
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod test_simulator {
+    use model_checker::simulator::{Simulator, run_simulator};
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+
+    fn build_ltl() -> Ltl {
+        // 0 -a-> 1 -b-> 2, 0 -c-> 0, 2 is a deadlock
+        let mut simple_ltl = Ltl::new(0, 3, 3);
+        simple_ltl.add_transition(0, "a", 1, false);
+        simple_ltl.add_transition(0, "c", 0, false);
+        simple_ltl.add_transition(1, "b", 2, false);
+        simple_ltl
+    }
+
+    #[test]
+    fn test_step_and_back() {
+        let ltl = build_ltl();
+        let mut simulator = Simulator::new(&ltl, 0);
+
+        assert_eq!(simulator.current_state(), 0);
+        assert_eq!(simulator.successors(), vec![(String::from("a"), 1), (String::from("c"), 0)]);
+
+        assert!(simulator.step(0));
+        assert!(simulator.step(0));
+        assert_eq!(simulator.current_state(), 2);
+        assert!(!simulator.step(0));
+
+        assert!(simulator.back());
+        assert_eq!(simulator.current_state(), 1);
+        assert!(simulator.back());
+        assert!(!simulator.back());
+    }
+
+    #[test]
+    fn test_random_walk_is_reproducible() {
+        let ltl = build_ltl();
+        let mut first = Simulator::new(&ltl, 42);
+        let mut second = Simulator::new(&ltl, 42);
+
+        first.random_walk(10);
+        second.random_walk(10);
+
+        assert_eq!(first.trace, second.trace);
+        // the walk can only end early in the deadlock
+        assert!(first.trace.len() == 10 || first.current_state() == 2);
+    }
+
+    #[test]
+    fn test_holds() {
+        let ltl = build_ltl();
+        let mut simulator = Simulator::new(&ltl, 0);
+        simulator.load_formula(&Formula::new(String::from("mu X. (<b>true || <a>X)"), false));
+
+        assert_eq!(simulator.holds(), vec![
+            (String::from("mu X. (<b>true || <a>X)"), true),
+            (String::from("<b>true"), false),
+        ]);
+        simulator.step(0);
+        simulator.step(0);
+        assert_eq!(simulator.holds(), vec![
+            (String::from("mu X. (<b>true || <a>X)"), false),
+            (String::from("<b>true"), false),
+        ]);
+    }
+
+    #[test]
+    fn test_run_simulator() {
+        let ltl = build_ltl();
+        let mut simulator = Simulator::new(&ltl, 0);
+        let mut output: Vec<u8> = Vec::new();
+
+        run_simulator(&mut simulator, "1\n0\nback\n7\nquit\n0\n".as_bytes(), &mut output).unwrap();
+
+        // c-loop, a, undo a, an invalid choice, and nothing after quit
+        assert_eq!(simulator.trace, vec![(0, String::from("c"), 0)]);
+        assert!(String::from_utf8(output).unwrap().contains("There is no transition 7"));
+    }
+}
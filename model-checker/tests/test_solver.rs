@@ -0,0 +1,258 @@
+#[cfg(test)]
+use model_checker::types::ltl::Ltl;
+
+#[cfg(test)]
+fn build_ltl() -> Ltl {
+    // a chain 0 -i-> 1 -i-> 2 -i-> 3 -plato-> 0, and a loop 1 -a-> 1
+    let mut simple_ltl = Ltl::new(0, 5, 4);
+    let transitions: [(i64, &str, i64); 5] = [
+        (0, "i", 1),
+        (1, "i", 2),
+        (2, "i", 3),
+        (3, "plato", 0),
+        (1, "a", 1),
+    ];
+    for (s, a, t) in transitions.iter() {
+        simple_ltl.add_transition(*s, a, *t, false);
+    }
+    simple_ltl
+}
+
+#[cfg(test)]
+mod test_cache {
+    use super::build_ltl;
+    use model_checker::solver::execute;
+    use model_checker::types::formula::Formula;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_closed_subformula() {
+        let ltl = build_ltl();
+        // <plato>true does not depend on Y, so it is only evaluated in the first iteration
+        let text = "mu Y. (<plato>true || <i>Y)";
+
+        let (result, statistics) = execute(Formula::new(text.to_string(), false), &ltl);
+
+        assert_eq!(result, HashSet::from([0, 1, 2, 3]));
+        assert_eq!(statistics.iterations, 5);
+        assert_eq!(statistics.cache_hits, 4);
+    }
+
+    #[test]
+    fn test_closed_fixed_point() {
+        let ltl = build_ltl();
+        // the inner fixed point is closed, so it is only calculated in the first of the 5 iterations of X
+        let text = "nu X. (<i>X && mu Y. (<plato>true || <i>Y))";
+
+        let (result, statistics) = execute(Formula::new(text.to_string(), false), &ltl);
+
+        assert_eq!(result, HashSet::new());
+        assert_eq!(statistics.iterations, 5 + 5);
+        assert_eq!(statistics.cache_hits, 4 + 4);
+    }
+}
+
+#[cfg(test)]
+mod test_statistics {
+    use model_checker::solver::find_formula_statistics;
+    use model_checker::types::formula::Formula;
+
+    use std::fs;
+
+    #[test]
+    fn test_statistics_of_input_formulas() {
+        // the nesting depth, alteration depth and dependent alteration depth. The ones marked with * have a closed fixed point
+        // inside one of the other type, which does not use the outer variable and so does not add to the dependent alteration depth
+        let expected: [(&str, (i64, i64, i64)); 22] = [
+            ("part1-tests/combined/form1.mcf", (2, 2, 1)), // *
+            ("part1-tests/combined/form2.mcf", (1, 1, 1)),
+            ("part1-tests/combined/form3.mcf", (2, 2, 2)),
+            ("part1-tests/combined/form4.mcf", (2, 2, 2)),
+            ("part1-tests/combined/form5.mcf", (1, 1, 1)),
+            ("part1-tests/fixpoints_only/form1.mcf", (1, 1, 1)),
+            ("part1-tests/fixpoints_only/form2.mcf", (1, 1, 1)),
+            ("part1-tests/fixpoints_only/form3.mcf", (2, 2, 2)),
+            ("part1-tests/fixpoints_only/form4.mcf", (2, 2, 2)),
+            ("part1-tests/fixpoints_only/form5.mcf", (2, 2, 1)), // *
+            ("part2-1/invariantly_inevitably_eat.mcf", (2, 2, 1)), // *
+            ("part2-1/invariantly_plato_starves.mcf", (2, 1, 1)),
+            ("part2-1/invariantly_possibly_eat.mcf", (2, 2, 1)), // *
+            ("part2-1/plato_infinitely_often_can_eat.mcf", (2, 2, 2)),
+            ("part2-2/can_ask_question.mcf", (1, 1, 1)),
+            ("part2-2/will_get_wisdom.mcf", (2, 2, 1)),
+            ("part2-3/infinite_run_no_access.mcf", (1, 1, 1)),
+            ("part2-3/infinitely_often_exclusive.mcf", (2, 2, 2)),
+            ("part2-3/invariantly_eventually_fair_shared_access.mcf", (3, 2, 1)), // *
+            ("part2-3/invariantly_inevitably_exclusive_access.mcf", (2, 2, 1)), // *
+            ("part2-3/invariantly_possibly_exclusive_access.mcf", (2, 2, 1)), // *
+            ("part1-tests/modal_operators/form1.mcf", (0, 0, 0)),
+        ];
+
+        for (file, statistics) in expected {
+            let contents = fs::read_to_string(format!("input/{}", file)).unwrap();
+            assert_eq!(find_formula_statistics(&Formula::new(contents, false).root_node), statistics, "{}", file);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_worklist {
+    use super::build_ltl;
+    use model_checker::solver::execute_improved;
+    use model_checker::types::formula::Formula;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_worklist() {
+        let ltl = build_ltl();
+        // after the first iteration only the i-predecessor of the state that was added to Y is re-examined
+        let text = "mu Y. (<plato>true || <i>Y)";
+
+        let (result, statistics) = execute_improved(Formula::new(text.to_string(), false), &ltl);
+
+        assert_eq!(result, HashSet::from([0, 1, 2, 3]));
+        assert_eq!(statistics.iterations, 5);
+        assert_eq!(statistics.state_updates, 2 * 3);
+    }
+}
+
+#[cfg(test)]
+mod test_justification {
+    use super::build_ltl;
+    use model_checker::solver::execute_justified;
+    use model_checker::types::formula::{Formula, Node};
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_justification() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("mu Y. (<plato>true || <i>Y)"), false);
+
+        let (result, justification) = execute_justified(&f, &ltl);
+
+        assert_eq!(result, HashSet::from([0, 1, 2, 3]));
+        assert!(justification.holds(&f.root_node, 0));
+        let Node::FixPointExpr { op: _, variable: _, rhs: body, surrounding_binder: _ } = &f.root_node else { unreachable!() };
+        let Node::BinaryExpr { op: _, lhs: _, rhs: diamond } = &**body else { unreachable!() };
+        let calculations = justification.calculations(&f.root_node);
+        assert_eq!(calculations.len(), 1);
+        // 3 can do plato right away, every state before it needs one more iteration of Y
+        let ranks: Vec<Option<i64>> = (0..4).map(|state| calculations[0].rank(body, state)).collect();
+        assert_eq!(ranks, vec![Some(4), Some(3), Some(2), Some(1)]);
+        assert!(!calculations[0].holds(body, 1, Some(2)));
+        assert!(calculations[0].holds(body, 1, Some(3)));
+        // 1 reaches Y with its i-transition, not with its a-loop, and 3 does not need Y
+        assert_eq!(calculations[0].successor(diamond, 1), Some(2));
+        assert_eq!(calculations[0].successor(diamond, 3), None);
+    }
+}
+
+#[cfg(test)]
+mod test_trace {
+    use super::build_ltl;
+    use model_checker::solver::{execute_with_options, Options, Trace};
+    use model_checker::types::formula::Formula;
+
+    #[test]
+    fn test_trace() {
+        let ltl = build_ltl();
+        let text = "nu X. (<i>X && mu Y. (<plato>true || <i>Y))";
+        let options = Options { trace: Trace::Sizes, ..Options::default() };
+
+        let (_, statistics) = execute_with_options(Formula::new(text.to_string(), false), &ltl, &options);
+
+        // Y is only calculated in the first iteration of X, which removes a state in every iteration
+        let records: Vec<(Vec<&str>, i64, usize)> = statistics.trace.iter()
+            .map(|record| (record.path.iter().map(|variable| variable.as_str()).collect(), record.iteration, record.size))
+            .collect();
+        assert_eq!(&records[..6], &[
+            (vec!["X", "Y"], 1, 1), (vec!["X", "Y"], 2, 2), (vec!["X", "Y"], 3, 3), (vec!["X", "Y"], 4, 4), (vec!["X", "Y"], 5, 4),
+            (vec!["X"], 1, 3),
+        ]);
+        assert_eq!(records.last(), Some(&(vec!["X"], 5, 0)));
+        assert_eq!(statistics.trace.len() as i64, statistics.iterations);
+        assert_eq!(statistics.trace[0].to_json(), r#"{"variable":"Y","path":["X","Y"],"iteration":1,"size":1}"#);
+
+        let options = Options { trace: Trace::States, ..Options::default() };
+        let (_, statistics) = execute_with_options(Formula::new(text.to_string(), false), &ltl, &options);
+        assert_eq!(statistics.trace[1].to_json(), r#"{"variable":"Y","path":["X","Y"],"iteration":2,"size":2,"states":[2,3]}"#);
+    }
+}
+
+#[cfg(test)]
+mod test_budget {
+    use super::build_ltl;
+    use model_checker::solver::{execute_with_options, execute_improved_with_options, Options, Stop};
+    use model_checker::types::formula::{Formula, Operator};
+
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    #[test]
+    fn test_budget() {
+        let ltl = build_ltl();
+        let text = "nu X. (<i>X && mu Y. (<plato>true || <i>Y))";
+
+        // the first iteration of X and two of Y are started, the third one of Y is not
+        let options = Options { max_iterations: Some(3), ..Options::default() };
+        for (_, statistics) in [
+            execute_with_options(Formula::new(text.to_string(), false), &ltl, &options),
+            execute_improved_with_options(Formula::new(text.to_string(), false), &ltl, &options),
+        ] {
+            assert_eq!(statistics.stopped, Some(Stop::MaxIterations));
+            let approximants: Vec<(&str, Operator, i64, HashSet<i64>)> = statistics.approximants.iter()
+                .map(|approximant| (approximant.variable.as_str(), approximant.op.clone(), approximant.iteration, approximant.states.clone()))
+                .collect();
+            assert_eq!(approximants, vec![
+                ("Y", Operator::LeastFixpoint, 2, HashSet::from([2, 3])),
+                ("X", Operator::GreatestFixpoint, 0, HashSet::from([0, 1, 2, 3])),
+            ]);
+            assert_eq!(statistics.approximants[0].path, vec!["X", "Y"]);
+        }
+
+        let options = Options { timeout: Some(Duration::ZERO), ..Options::default() };
+        let (_, statistics) = execute_with_options(Formula::new(text.to_string(), false), &ltl, &options);
+        assert_eq!(statistics.stopped, Some(Stop::Timeout));
+
+        // nothing was calculated when it was cancelled, so there are no approximants
+        let options = Options::default();
+        options.cancellation.cancel();
+        let (_, statistics) = execute_with_options(Formula::new(text.to_string(), false), &ltl, &options);
+        assert_eq!((statistics.stopped, statistics.iterations), (Some(Stop::Cancelled), 0));
+        assert!(statistics.approximants.is_empty());
+
+        let (_, statistics) = execute_with_options(Formula::new(text.to_string(), false), &ltl, &Options::default());
+        assert_eq!(statistics.stopped, None);
+    }
+}
+
+#[cfg(test)]
+mod test_threads {
+    use model_checker::generators::random_lts;
+    use model_checker::solver::{execute_with_options, execute_improved_with_options, Options, Trace};
+    use model_checker::types::formula::Formula;
+
+    #[test]
+    fn test_same_result_statistics_and_trace() {
+        // enough states to split the modalities over the threads
+        let ltl = random_lts(8192, 2, 3, 0);
+        let formulas = [
+            "(nu X. ([a1]X && mu Y. (<a0>true || <a2>Y)) && <a1>true)",
+            "nu X. (<a0>X && (mu Y. (<a2>true || [a1]Y) || <a1>mu Z. <a0>Z))",
+        ];
+
+        // the trace is in the same order as well
+        let options = |threads: usize| Options { threads, trace: Trace::Sizes, ..Options::default() };
+        for text in formulas {
+            let expected = execute_with_options(Formula::new(text.to_string(), false), &ltl, &options(1));
+            let expected_improved = execute_improved_with_options(Formula::new(text.to_string(), false), &ltl, &options(1));
+            for threads in [2, 3] {
+                assert_eq!(execute_with_options(Formula::new(text.to_string(), false), &ltl, &options(threads)), expected, "{}", text);
+                assert_eq!(execute_improved_with_options(Formula::new(text.to_string(), false), &ltl, &options(threads)), expected_improved, "{}", text);
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod test_symbolic {
+    use model_checker::bdd::{Bdd, BddManager};
+    use model_checker::generators::random_lts;
+    use model_checker::solver::execute;
+    use model_checker::symbolic::execute_symbolic;
+    use model_checker::types::formula::Formula;
+
+    #[test]
+    fn test_canonical() {
+        let mut manager = BddManager::new();
+        let x = manager.cube(&[(0, true)]);
+        let y = manager.cube(&[(1, true)]);
+        let not_x = manager.not(x);
+
+        // x && y built in two ways is the same node, and (x || y) && !x is !x && y
+        let x_and_y = manager.and(x, y);
+        assert_eq!(x_and_y, manager.cube(&[(1, true), (0, true)]));
+        let x_or_y = manager.or(x, y);
+        let rest = manager.and(x_or_y, not_x);
+        assert_eq!(rest, manager.cube(&[(0, false), (1, true)]));
+        assert_eq!(manager.or(x, not_x), Bdd::TRUE);
+
+        // exists x. (x && y) is y
+        assert_eq!(manager.and_exists(x, y, x), y);
+    }
+
+    #[test]
+    fn test_same_result_as_explicit() {
+        let formulas = [
+            "mu X. (<a0>true || <a1>X)",
+            "nu X. mu Y. ((<a0>X || <a1>Y) || <a2>Y)",
+            "mu X. nu Y. (([a0]X && [a1]Y) && <a2>true)",
+            "(nu X. ([a1]X && mu Y. (<a0>true || <a2>Y)) && [b]false)",
+            "nu X. (<a0>X && Z)",
+        ];
+
+        for seed in 0..3 {
+            let ltl = random_lts(11, 2, 3, seed);
+            for text in formulas {
+                let (expected, _) = execute(Formula::new(text.to_string(), false), &ltl);
+                let (result, _, _) = execute_symbolic(Formula::new(text.to_string(), false), &ltl);
+                assert_eq!(result, expected, "{} on seed {}", text, seed);
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod test_transition_system {
+    use model_checker::solver::{execute, execute_improved};
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+    use model_checker::types::transition_system::TransitionSystem;
+
+    use std::collections::HashSet;
+
+    // A counter modulo n that is never stored: `tick` increments it, `reset` goes back to 0 from the odd states
+    struct Counter {
+        n: i64,
+    }
+
+    impl TransitionSystem for Counter {
+        fn states(&self) -> Vec<i64> {
+            (0..self.n).collect()
+        }
+
+        fn initial_state(&self) -> i64 {
+            0
+        }
+
+        fn successors(&self, state: i64, label: &str) -> Vec<i64> {
+            match label {
+                "tick" => vec![(state + 1) % self.n],
+                "reset" if state % 2 == 1 => vec![0],
+                _ => vec![],
+            }
+        }
+
+        fn predecessors(&self, state: i64, label: &str) -> Vec<i64> {
+            match label {
+                "tick" => vec![(state + self.n - 1) % self.n],
+                "reset" if state == 0 => (0..self.n).filter(|s| s % 2 == 1).collect(),
+                _ => vec![],
+            }
+        }
+
+        fn labels(&self) -> HashSet<String> {
+            HashSet::from([String::from("tick"), String::from("reset")])
+        }
+    }
+
+    fn build_ltl(n: i64) -> Ltl {
+        let counter = Counter { n };
+        let mut ltl = Ltl::new(0, 0, n);
+        for state in 0..n {
+            for label in counter.labels() {
+                for target in counter.successors(state, &label) {
+                    ltl.add_transition(state, &label, target, false);
+                }
+            }
+        }
+        ltl
+    }
+
+    #[test]
+    fn test_modalities() {
+        let counter = Counter { n: 5 };
+        let ltl = build_ltl(5);
+
+        for label in ["tick", "reset", "other"] {
+            for states in [HashSet::new(), HashSet::from([0]), HashSet::from([1, 2, 3])] {
+                assert_eq!(counter.get_diamond_modality(label.to_string(), states.clone()),
+                    ltl.get_diamond_modality(label.to_string(), states.clone()));
+                assert_eq!(counter.get_box_modality(label.to_string(), states.clone()),
+                    ltl.get_box_modality(label.to_string(), states.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_solvers_on_implicit_system() {
+        let counter = Counter { n: 6 };
+        let ltl = build_ltl(6);
+        let formulas = [
+            "nu X. ([tick]X && <tick>true)",
+            "mu X. (<reset>true || <tick>X)",
+            "nu X. mu Y. ((<reset>X || <tick>Y))",
+            "mu X. [tick]X",
+        ];
+
+        for text in formulas {
+            let (expected, _) = execute(Formula::new(text.to_string(), false), &ltl);
+            assert_eq!(execute(Formula::new(text.to_string(), false), &counter).0, expected);
+            assert_eq!(execute_improved(Formula::new(text.to_string(), false), &counter).0, expected);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod test_witness {
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+    use model_checker::witness::{find_witness, Witness};
+
+    fn build_ltl() -> Ltl {
+        // 0 -i-> 1 -i-> 2 -plato-> 3 -i-> 0, and a shortcut 0 -others-> 2, 4 is a deadlock reachable with 1 -i-> 4
+        let mut simple_ltl = Ltl::new(0, 6, 5);
+        let transitions: [(i64, &str, i64); 6] = [
+            (0, "i", 1),
+            (1, "i", 2),
+            (2, "plato", 3),
+            (3, "i", 0),
+            (0, "others", 2),
+            (1, "i", 4),
+        ];
+        for (s, a, t) in transitions.iter() {
+            simple_ltl.add_transition(*s, a, *t, false);
+        }
+        simple_ltl
+    }
+
+    fn step(source: i64, label: &str, target: i64) -> (i64, String, i64) {
+        (source, label.to_string(), target)
+    }
+
+    #[test]
+    fn test_reachability_witness() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("mu Y. (<plato>true || (<i>Y || <others>Y))"), false);
+
+        // the least fixed point makes the witness take a shortest path
+        assert_eq!(find_witness(&f, &ltl), Some(Witness {
+            prefix: vec![step(0, "others", 2), step(2, "plato", 3)],
+            cycle: vec![],
+        }));
+    }
+
+    #[test]
+    fn test_lasso_witness() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("nu X. mu Y. ((<plato>X || <i>Y) || <others>Y)"), false);
+
+        assert_eq!(find_witness(&f, &ltl), Some(Witness {
+            prefix: vec![],
+            cycle: vec![step(0, "others", 2), step(2, "plato", 3), step(3, "i", 0)],
+        }));
+    }
+
+    #[test]
+    fn test_witness_ignores_boxes() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("([others]false || <i><i>[plato]false)"), false);
+
+        // the first disjunct does not hold in 0, the second is shown by going to the deadlock
+        assert_eq!(find_witness(&f, &ltl), Some(Witness {
+            prefix: vec![step(0, "i", 1), step(1, "i", 4)],
+            cycle: vec![],
+        }));
+    }
+
+    #[test]
+    fn test_no_witness() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("nu X. (<i>X || <others>X)"), false);
+
+        assert_eq!(find_witness(&f, &ltl), None);
+    }
+}
+
+
+#[cfg(test)]
+mod test_counterexample {
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+    use model_checker::witness::{find_counterexample, Witness};
+
+    fn build_ltl(nr_of_states: i64, transitions: &[(i64, &str, i64)]) -> Ltl {
+        let mut ltl = Ltl::new(0, transitions.len() as i64, nr_of_states);
+        for (s, a, t) in transitions.iter() {
+            ltl.add_transition(*s, a, *t, false);
+        }
+        ltl
+    }
+
+    fn step(source: i64, label: &str, target: i64) -> (i64, String, i64) {
+        (source, label.to_string(), target)
+    }
+
+    #[test]
+    fn test_path_to_deadlock() {
+        // 4 is a deadlock reachable with 1 -i-> 4
+        let ltl = build_ltl(5, &[(0, "i", 1), (1, "i", 2), (2, "plato", 3), (3, "i", 0), (0, "others", 2), (1, "i", 4)]);
+        let f = Formula::new(String::from("nu X. (([i]X && [others]X) && (<i>true || <plato>true))"), false);
+
+        let counterexample = find_counterexample(&f, &ltl);
+
+        assert_eq!(counterexample, Some(Witness {
+            prefix: vec![step(0, "i", 1), step(1, "i", 4)],
+            cycle: vec![],
+        }));
+        let (sub_ltl, original) = counterexample.unwrap().to_ltl(0);
+        assert_eq!(original, vec![0, 1, 4]);
+        assert_eq!(sub_ltl.nr_of_states, 3);
+        assert!(sub_ltl.transitions[&1]["i"].contains(&2));
+    }
+
+    #[test]
+    fn test_lasso_counterexample() {
+        // plato can always eat, but the run that keeps doing i never does
+        let ltl = build_ltl(3, &[(0, "i", 1), (1, "i", 0), (0, "plato", 2)]);
+        let f = Formula::new(String::from("mu Y. ([i]Y && [plato]Y)"), false);
+
+        assert_eq!(find_counterexample(&f, &ltl), Some(Witness {
+            prefix: vec![step(0, "i", 1)],
+            cycle: vec![step(1, "i", 0), step(0, "i", 1)],
+        }));
+    }
+
+    #[test]
+    fn test_first_violation() {
+        // both i-successors of 0 reach a deadlock, 2 is one and 1 has to take another step
+        let ltl = build_ltl(4, &[(0, "i", 1), (0, "i", 2), (1, "i", 3)]);
+        let f = Formula::new(String::from("nu X. ([i]X && <i>true)"), false);
+
+        // the approximants of X lose 2 before 1, so the counterexample goes to 2
+        assert_eq!(find_counterexample(&f, &ltl), Some(Witness {
+            prefix: vec![step(0, "i", 2)],
+            cycle: vec![],
+        }));
+    }
+
+    #[test]
+    fn test_no_counterexample() {
+        let ltl = build_ltl(3, &[(0, "i", 1), (1, "i", 0), (0, "plato", 2)]);
+        let f = Formula::new(String::from("nu X. ([i]X && mu Y. (<plato>true || <i>Y))"), false);
+
+        assert_eq!(find_counterexample(&f, &ltl), None);
+    }
+}
+
+
+#[cfg(test)]
+mod test_evidence {
+    use model_checker::generators::random_lts;
+    use model_checker::solver::execute;
+    use model_checker::types::formula::Formula;
+    use model_checker::types::ltl::Ltl;
+    use model_checker::witness::find_evidence;
+
+    use std::collections::BTreeSet;
+
+    fn build_ltl() -> Ltl {
+        // a loop 0 -a-> 1 -a-> 0 from which b is always possible, and a c-transition that leaves it
+        let mut simple_ltl = Ltl::new(0, 4, 4);
+        let transitions: [(i64, &str, i64); 4] = [
+            (0, "a", 1),
+            (1, "a", 0),
+            (1, "b", 2),
+            (0, "c", 3),
+        ];
+        for (s, a, t) in transitions.iter() {
+            simple_ltl.add_transition(*s, a, *t, false);
+        }
+        simple_ltl
+    }
+
+    #[test]
+    fn test_evidence() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("nu X. ([a]X && mu Y. (<b>true || <a>Y))"), false);
+
+        let evidence = find_evidence(&f, &ltl).unwrap();
+
+        // all a-transitions for the box, and one transition for each diamond, the c-transition is not needed
+        assert_eq!(evidence.transitions, BTreeSet::from([
+            (0, String::from("a"), 1),
+            (1, String::from("a"), 0),
+            (1, String::from("b"), 2),
+        ]));
+        assert_eq!(evidence.states.keys().cloned().collect::<Vec<i64>>(), vec![0, 1, 2]);
+        assert!(evidence.states[&0].contains("<a>Y"));
+        assert!(evidence.states[&1].contains("<b>true"));
+        assert!(evidence.states[&1].contains("[a]X"));
+
+        let (sub_ltl, original) = evidence.to_ltl(0);
+        assert_eq!(original, vec![0, 1, 2]);
+        assert_eq!(sub_ltl.nr_of_states, 3);
+    }
+
+    #[test]
+    fn test_evidence_satisfies_formula() {
+        let text = "nu X. ([a0]X && mu Y. ((<a1>true || <a0>Y) || <a2>Y))";
+
+        let mut found = 0;
+        for seed in 0..10 {
+            let ltl = random_lts(200, 2, 3, seed);
+            let Some(evidence) = find_evidence(&Formula::new(text.to_string(), false), &ltl) else { continue };
+            // the sub-LTS has all the transitions the formula needs
+            let (sub_ltl, _) = evidence.to_ltl(ltl.first_state);
+            let (result, _) = execute(Formula::new(text.to_string(), false), &sub_ltl);
+            assert!(result.contains(&0), "seed {}", seed);
+            found += 1;
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn test_no_evidence() {
+        let ltl = build_ltl();
+        let f = Formula::new(String::from("nu X. ([c]X && <a>true)"), false);
+
+        assert_eq!(find_evidence(&f, &ltl), None);
+    }
+}
@@ -1,11 +1,13 @@
-use std::fs;
+use std::io::BufRead;
 use clap::Parser;
 use core::cmp::max;
 
 mod types;
 mod solver;
+mod compression;
 
 use solver::{main_algo};
+use compression::{open_file, strip_compression_extension};
 
 use types::progress_measure::ProgressMeasure;
 use types::vertex::Vertex;
@@ -19,7 +21,7 @@ use types::vertex::Vertices;
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// The path to the .gm file
+    /// The path to the .gm file, which may be compressed with gzip or bzip2
     #[arg(short, long)]
     gm_file: std::path::PathBuf,
 
@@ -71,14 +73,14 @@ fn read_gm_file(file_path: std::path::PathBuf) -> (ProgressMeasure, Vertices, i6
     if !file_path.exists() {
         panic!("File {:?} does not exist", file_path);
     }
-    if "gm" != file_path.extension().unwrap() {
+    // The file may be compressed, e.g. `dining_2.invariantly_inevitably_eat.gm.gz`
+    if Some(std::ffi::OsStr::new("gm")) != strip_compression_extension(&file_path).extension() {
         panic!("File {:?} should have been of type .gm", file_path);
     }
 
-    let contents: String = fs::read_to_string(file_path)
-        .expect("Should have been able to read the file");
-
-    let mut lines = contents.lines();
+    // Read the (possibly compressed) file line by line, so we never hold the whole file in memory
+    let mut lines = open_file(&file_path).lines()
+        .map(|line| line.expect("Should have been able to read the file"));
 
     // Initialize header, e.g. "parity 118206;"
     let first_line = lines.nth(0)
@@ -101,7 +103,7 @@ fn read_gm_file(file_path: std::path::PathBuf) -> (ProgressMeasure, Vertices, i6
     // Place to store all vertices
     const NONE: Option<Vertex> = None;
     let mut vertices: Vertices = vec![NONE; (max_identifier+1) as usize];
-    for part in lines.skip(0) {
+    for part in lines {
         // remove ; and split into parts
         let part_split = part[0..part.len()-1].split(" ").collect::<Vec<&str>>();
 
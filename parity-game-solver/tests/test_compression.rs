@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod test_open_file {
+    use parity_game_solver::compression::{open_file, strip_compression_extension};
+    use bzip2::write::BzEncoder;
+    use flate2::write::GzEncoder;
+    use std::fs;
+    use std::io::{BufRead, Write};
+    use std::path::{Path, PathBuf};
+
+    const CONTENTS: &str = "first line\nsecond line\n";
+
+    fn read_lines(file_path: &Path) -> Vec<String> {
+        open_file(file_path).lines().map(|line| line.unwrap()).collect()
+    }
+
+    fn write_temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let file_path = std::env::temp_dir().join(format!("parity_game_solver_{}_{}", std::process::id(), name));
+        fs::write(&file_path, bytes).unwrap();
+        file_path
+    }
+
+    #[test]
+    fn test_plain_file() {
+        let file_path = write_temp_file("plain.txt", CONTENTS.as_bytes());
+
+        assert_eq!(read_lines(&file_path), vec!["first line", "second line"]);
+        fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn test_gzip_file() {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(CONTENTS.as_bytes()).unwrap();
+        // no .gz extension, the compression is detected from the magic bytes
+        let file_path = write_temp_file("gzip.txt", &encoder.finish().unwrap());
+
+        assert_eq!(read_lines(&file_path), vec!["first line", "second line"]);
+        fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn test_bzip2_file() {
+        let mut encoder = BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(CONTENTS.as_bytes()).unwrap();
+        let file_path = write_temp_file("bzip2.txt", &encoder.finish().unwrap());
+
+        assert_eq!(read_lines(&file_path), vec!["first line", "second line"]);
+        fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn test_strip_compression_extension() {
+        assert_eq!(strip_compression_extension(Path::new("input/a.aut.gz")), PathBuf::from("input/a.aut"));
+        assert_eq!(strip_compression_extension(Path::new("input/a.gm.bz2")), PathBuf::from("input/a.gm"));
+        assert_eq!(strip_compression_extension(Path::new("input/a.gm")), PathBuf::from("input/a.gm"));
+    }
+}